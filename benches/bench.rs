@@ -1,5 +1,6 @@
 use bencher::Bencher;
-use crc32fast::Hasher;
+use crc32fast::generic::{Crc32, CRC_32_BZIP2};
+use crc32fast::{crc32c, crc64, Hasher};
 use rand::Rng;
 
 fn bench(b: &mut Bencher, size: usize, hasher_init: Hasher) {
@@ -35,6 +36,91 @@ fn bench_megabyte_specialized(b: &mut Bencher) {
     )
 }
 
+fn bench_crc32c_inner(b: &mut Bencher, size: usize, hasher_init: crc32c::Hasher) {
+    let mut bytes = vec![0u8; size];
+    rand::thread_rng().fill(&mut bytes[..]);
+
+    b.iter(|| {
+        let mut hasher = hasher_init.clone();
+        hasher.update(&bytes);
+        bencher::black_box(hasher.finalize())
+    });
+
+    b.bytes = size as u64;
+}
+
+fn bench_crc32c_kilobyte_baseline(b: &mut Bencher) {
+    bench_crc32c_inner(b, 1024, crc32c::Hasher::internal_new_baseline(0, 0))
+}
+
+fn bench_crc32c_kilobyte_specialized(b: &mut Bencher) {
+    bench_crc32c_inner(
+        b,
+        1024,
+        crc32c::Hasher::internal_new_specialized(0, 0).unwrap(),
+    )
+}
+
+fn bench_crc32c_megabyte_baseline(b: &mut Bencher) {
+    bench_crc32c_inner(b, 1024 * 1024, crc32c::Hasher::internal_new_baseline(0, 0))
+}
+
+fn bench_crc32c_megabyte_specialized(b: &mut Bencher) {
+    bench_crc32c_inner(
+        b,
+        1024 * 1024,
+        crc32c::Hasher::internal_new_specialized(0, 0).unwrap(),
+    )
+}
+
+fn bench_crc64_inner(b: &mut Bencher, size: usize, hasher_init: crc64::Hasher) {
+    let mut bytes = vec![0u8; size];
+    rand::thread_rng().fill(&mut bytes[..]);
+
+    b.iter(|| {
+        let mut hasher = hasher_init.clone();
+        hasher.update(&bytes);
+        bencher::black_box(hasher.finalize())
+    });
+
+    b.bytes = size as u64;
+}
+
+fn bench_crc64_megabyte_baseline(b: &mut Bencher) {
+    bench_crc64_inner(
+        b,
+        1024 * 1024,
+        crc64::Hasher::internal_new_baseline(crc64::Variant::Xz, 0, 0),
+    )
+}
+
+fn bench_crc64_megabyte_specialized(b: &mut Bencher) {
+    bench_crc64_inner(
+        b,
+        1024 * 1024,
+        crc64::Hasher::internal_new_specialized(crc64::Variant::Xz, 0, 0).unwrap(),
+    )
+}
+
+static BZIP2: Crc32 = Crc32::new(CRC_32_BZIP2);
+
+fn bench_generic_inner(b: &mut Bencher, size: usize, crc: &Crc32) {
+    let mut bytes = vec![0u8; size];
+    rand::thread_rng().fill(&mut bytes[..]);
+
+    b.iter(|| bencher::black_box(crc.checksum(&bytes)));
+
+    b.bytes = size as u64;
+}
+
+fn bench_generic_kilobyte_bzip2(b: &mut Bencher) {
+    bench_generic_inner(b, 1024, &BZIP2)
+}
+
+fn bench_generic_megabyte_bzip2(b: &mut Bencher) {
+    bench_generic_inner(b, 1024 * 1024, &BZIP2)
+}
+
 fn bench_combine_inner(b: &mut Bencher, i1: u32, l1: u64, i2: u32, l2: u64) {
     let h1 = Hasher::new_with_initial_len(i1, l1);
     let h2 = Hasher::new_with_initial_len(i2, l2);
@@ -77,4 +163,28 @@ bencher::benchmark_group!(
     bench_combine_32,
     bench_combine_64
 );
-bencher::benchmark_main!(bench_baseline, bench_specialized, bench_combine);
+bencher::benchmark_group!(
+    bench_crc32c,
+    bench_crc32c_kilobyte_baseline,
+    bench_crc32c_kilobyte_specialized,
+    bench_crc32c_megabyte_baseline,
+    bench_crc32c_megabyte_specialized
+);
+bencher::benchmark_group!(
+    bench_generic,
+    bench_generic_kilobyte_bzip2,
+    bench_generic_megabyte_bzip2
+);
+bencher::benchmark_group!(
+    bench_crc64,
+    bench_crc64_megabyte_baseline,
+    bench_crc64_megabyte_specialized
+);
+bencher::benchmark_main!(
+    bench_baseline,
+    bench_specialized,
+    bench_combine,
+    bench_crc32c,
+    bench_generic,
+    bench_crc64
+);
@@ -0,0 +1,206 @@
+//! Error detection properties of CRC polynomials.
+//!
+//! An error goes undetected by a CRC if the flipped bits, as a polynomial over
+//! the whole codeword, are a multiple of the CRC polynomial. [`analyze`] counts
+//! such errors of 2, 3 and 4 bits for data words of a range of lengths, which
+//! gives the Hamming distance of the polynomial as a function of the length,
+//! as tabulated by Philip Koopman.
+//!
+//! ```rust
+//! use crc32fast::{analysis, gf2};
+//!
+//! // The CRC-32C polynomial is divisible by x + 1, and so detects all errors
+//! // flipping an odd number of bits.
+//! let analysis = analysis::analyze(&gf2::CRC32C, 256);
+//! assert_eq!(analysis.undetectable(3, 256), 0);
+//! assert!(analysis.hamming_distance(256) > 4);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::gf2::Poly32;
+
+/// The error detection properties of a polynomial, for data words of up to a
+/// given length.
+#[derive(Clone)]
+pub struct Analysis {
+    max_data_bits: usize,
+    // For weights 2, 3 and 4, the number of undetectable errors whose last
+    // flipped bit is at each position of the codeword, counting from x^0.
+    undetectable: [Vec<u64>; 3],
+}
+
+/// Analyzes `poly` for data words of up to `max_data_bits` bits, which are
+/// followed by the 32 bits of the CRC in each codeword.
+///
+/// This takes time and memory quadratic in the length of the codeword, so it
+/// is practical up to a few thousand bits.
+pub fn analyze(poly: &Poly32, max_data_bits: usize) -> Analysis {
+    let len = max_data_bits + 32;
+    let mut undetectable = [vec![0; len], vec![0; len], vec![0; len]];
+
+    // The syndrome of flipping bit i of the codeword is x^i modulo the
+    // polynomial, and an error is undetectable if its syndromes add up to 0.
+    let mut syndromes = Vec::with_capacity(len);
+    let mut syndrome = Poly32::ONE;
+    for _ in 0..len {
+        syndromes.push(syndrome);
+        syndrome = poly.mul(syndrome, Poly32::X);
+    }
+
+    // For each k, errors of 2 bits i < k, 3 bits i < k < l and 4 bits
+    // i < j < k < l are found by looking up the single bits and pairs of bits
+    // below k with the right syndrome.
+    let mut singles: HashMap<u32, u64> = HashMap::new();
+    let mut pairs: HashMap<u32, u64> = HashMap::new();
+    for (k, &syndrome_k) in syndromes.iter().enumerate() {
+        undetectable[0][k] += singles.get(&syndrome_k).copied().unwrap_or(0);
+        for (l, &syndrome_l) in syndromes.iter().enumerate().skip(k + 1) {
+            let syndrome = syndrome_k ^ syndrome_l;
+            undetectable[1][l] += singles.get(&syndrome).copied().unwrap_or(0);
+            undetectable[2][l] += pairs.get(&syndrome).copied().unwrap_or(0);
+        }
+
+        for &syndrome_i in &syndromes[..k] {
+            *pairs.entry(syndrome_i ^ syndrome_k).or_insert(0) += 1;
+        }
+        *singles.entry(syndrome_k).or_insert(0) += 1;
+    }
+
+    // Turn the counts per position into counts per codeword length.
+    for counts in undetectable.iter_mut() {
+        for i in 1..counts.len() {
+            counts[i] += counts[i - 1];
+        }
+    }
+
+    Analysis {
+        max_data_bits,
+        undetectable,
+    }
+}
+
+impl Analysis {
+    /// Returns the maximum length of the data words (in bits) analyzed.
+    pub fn max_data_bits(&self) -> usize {
+        self.max_data_bits
+    }
+
+    /// Returns the number of undetectable errors flipping exactly `weight` bits
+    /// of a codeword with a data word of `data_bits` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is not 2, 3 or 4, or `data_bits` is larger than
+    /// `max_data_bits`.
+    pub fn undetectable(&self, weight: u32, data_bits: usize) -> u64 {
+        assert!((2..=4).contains(&weight), "unsupported weight");
+        assert!(data_bits <= self.max_data_bits, "data word too long");
+        self.undetectable[weight as usize - 2][data_bits + 31]
+    }
+
+    /// Returns the Hamming distance for data words of `data_bits` bits, that
+    /// is the smallest number of flipped bits that can go undetected.
+    ///
+    /// Only errors of up to 4 bits are examined, so a result of 5 means that
+    /// the Hamming distance is at least 5.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data_bits` is larger than `max_data_bits`.
+    pub fn hamming_distance(&self, data_bits: usize) -> u32 {
+        (2..=4)
+            .find(|&weight| self.undetectable(weight, data_bits) != 0)
+            .unwrap_or(5)
+    }
+
+    /// Returns the largest length of data words (in bits), up to
+    /// `max_data_bits`, for which the Hamming distance is at least `hd`.
+    ///
+    /// Returns `None` if it is smaller for all lengths, including empty data
+    /// words.
+    pub fn max_data_bits_for(&self, hd: u32) -> Option<usize> {
+        (0..=self.max_data_bits)
+            .rev()
+            .find(|&data_bits| self.hamming_distance(data_bits) >= hd)
+    }
+}
+
+impl fmt::Debug for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("crc32fast::analysis::Analysis")
+            .field("max_data_bits", &self.max_data_bits)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::analyze;
+    use crate::gf2::{Poly32, CRC32, CRC32C};
+
+    // Counts the undetectable errors by trying all of them.
+    fn brute_force(poly: &Poly32, data_bits: usize) -> [u64; 3] {
+        let len = data_bits + 32;
+        let syndromes: Vec<u32> = (0..len as u64).map(|i| poly.xpow(i)).collect();
+        let mut counts = [0; 3];
+        for i in 0..len {
+            for j in i + 1..len {
+                let s2 = syndromes[i] ^ syndromes[j];
+                counts[0] += u64::from(s2 == 0);
+                for k in j + 1..len {
+                    let s3 = s2 ^ syndromes[k];
+                    counts[1] += u64::from(s3 == 0);
+                    for &s4 in &syndromes[k + 1..] {
+                        counts[2] += u64::from(s3 ^ s4 == 0);
+                    }
+                }
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn against_brute_force() {
+        // x^32 + x^3 + 1 has plenty of undetectable errors at short lengths.
+        let poly = Poly32::new(0x00000009);
+        let analysis = analyze(&poly, 8);
+        for data_bits in [0, 1, 5, 8] {
+            let counts = brute_force(&poly, data_bits);
+            for weight in 2..=4 {
+                assert_eq!(
+                    analysis.undetectable(weight, data_bits),
+                    counts[weight as usize - 2]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn golden() {
+        // x^32 + 1 misses two flipped bits 32 bits apart.
+        let analysis = analyze(&Poly32::new(0x00000001), 2);
+        assert_eq!(analysis.undetectable(2, 0), 0);
+        assert_eq!(analysis.undetectable(2, 1), 1);
+        assert_eq!(analysis.undetectable(2, 2), 2);
+        assert_eq!(analysis.hamming_distance(0), 5);
+        assert_eq!(analysis.hamming_distance(1), 2);
+        assert_eq!(analysis.max_data_bits_for(3), Some(0));
+
+        // Any polynomial catches all errors in the 32 bits of the CRC alone,
+        // but x^32 + x^3 + 1 itself is an undetectable error of 3 bits.
+        let analysis = analyze(&Poly32::new(0x00000009), 1);
+        assert_eq!(analysis.hamming_distance(0), 5);
+        assert_eq!(analysis.hamming_distance(1), 3);
+        assert_eq!(analysis.undetectable(3, 1), 1);
+
+        // Koopman lists the CRC-32 polynomial with a Hamming distance of 6 up
+        // to 268 bits, which is beyond what is examined here.
+        let analysis = analyze(&CRC32, 268);
+        assert_eq!(analysis.max_data_bits_for(5), Some(268));
+        assert_eq!(analysis.undetectable(4, 268), 0);
+        let analysis = analyze(&CRC32C, 64);
+        assert_eq!(analysis.undetectable(3, 64), 0);
+    }
+}
@@ -22,12 +22,31 @@ impl State {
         self.state = 0;
     }
 
-    pub fn combine(&mut self, other: u32, amount: u64) {
-        self.state = crate::combine::combine(self.state, other, amount);
+    pub fn update_zeros(&mut self, op: &crate::CombineOp) {
+        self.state = op.append_zeros(self.state);
     }
+
+    pub fn combine(&mut self, other: u32, op: &crate::CombineOp) {
+        self.state = op.apply(self.state, other);
+    }
+
+    pub fn combine_op(&self, len: u64) -> crate::CombineOp {
+        crate::CombineOp::new(len)
+    }
+}
+
+pub(crate) fn update_fast_16(prev: u32, buf: &[u8]) -> u32 {
+    update_fast_16_with(&CRC32_TABLE, prev, buf)
 }
 
-pub(crate) fn update_fast_16(prev: u32, mut buf: &[u8]) -> u32 {
+#[cfg(test)]
+pub(crate) fn update_slow(prev: u32, buf: &[u8]) -> u32 {
+    update_slow_with(&CRC32_TABLE, prev, buf)
+}
+
+// Slice-by-16 update for any reflected 32-bit polynomial, given its tables.
+#[inline(always)]
+pub(crate) fn update_fast_16_with(table: &[[u32; 256]; 16], prev: u32, mut buf: &[u8]) -> u32 {
     const UNROLL: usize = 4;
     const BYTES_AT_ONCE: usize = 16 * UNROLL;
 
@@ -36,34 +55,35 @@ pub(crate) fn update_fast_16(prev: u32, mut buf: &[u8]) -> u32 {
     while buf.len() >= BYTES_AT_ONCE {
         for _ in 0..UNROLL {
             let w0 = u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) ^ crc;
-            crc = CRC32_TABLE[0x0][buf[0xf] as usize]
-                ^ CRC32_TABLE[0x1][buf[0xe] as usize]
-                ^ CRC32_TABLE[0x2][buf[0xd] as usize]
-                ^ CRC32_TABLE[0x3][buf[0xc] as usize]
-                ^ CRC32_TABLE[0x4][buf[0xb] as usize]
-                ^ CRC32_TABLE[0x5][buf[0xa] as usize]
-                ^ CRC32_TABLE[0x6][buf[0x9] as usize]
-                ^ CRC32_TABLE[0x7][buf[0x8] as usize]
-                ^ CRC32_TABLE[0x8][buf[0x7] as usize]
-                ^ CRC32_TABLE[0x9][buf[0x6] as usize]
-                ^ CRC32_TABLE[0xa][buf[0x5] as usize]
-                ^ CRC32_TABLE[0xb][buf[0x4] as usize]
-                ^ CRC32_TABLE[0xc][(w0 >> 24) as usize]
-                ^ CRC32_TABLE[0xd][((w0 >> 16) & 0xFF) as usize]
-                ^ CRC32_TABLE[0xe][((w0 >> 8) & 0xFF) as usize]
-                ^ CRC32_TABLE[0xf][(w0 & 0xFF) as usize];
+            crc = table[0x0][buf[0xf] as usize]
+                ^ table[0x1][buf[0xe] as usize]
+                ^ table[0x2][buf[0xd] as usize]
+                ^ table[0x3][buf[0xc] as usize]
+                ^ table[0x4][buf[0xb] as usize]
+                ^ table[0x5][buf[0xa] as usize]
+                ^ table[0x6][buf[0x9] as usize]
+                ^ table[0x7][buf[0x8] as usize]
+                ^ table[0x8][buf[0x7] as usize]
+                ^ table[0x9][buf[0x6] as usize]
+                ^ table[0xa][buf[0x5] as usize]
+                ^ table[0xb][buf[0x4] as usize]
+                ^ table[0xc][(w0 >> 24) as usize]
+                ^ table[0xd][((w0 >> 16) & 0xFF) as usize]
+                ^ table[0xe][((w0 >> 8) & 0xFF) as usize]
+                ^ table[0xf][(w0 & 0xFF) as usize];
             buf = &buf[16..];
         }
     }
 
-    update_slow(!crc, buf)
+    update_slow_with(table, !crc, buf)
 }
 
-pub(crate) fn update_slow(prev: u32, buf: &[u8]) -> u32 {
+#[inline(always)]
+pub(crate) fn update_slow_with(table: &[[u32; 256]; 16], prev: u32, buf: &[u8]) -> u32 {
     let mut crc = !prev;
 
     for &byte in buf.iter() {
-        crc = CRC32_TABLE[0][((crc as u8) ^ byte) as usize] ^ (crc >> 8);
+        crc = table[0][((crc as u8) ^ byte) as usize] ^ (crc >> 8);
     }
 
     !crc
@@ -0,0 +1,405 @@
+//! Content-defined chunking, using the rolling CRC32 as fingerprint.
+//!
+//! A [`Chunker`] splits data into chunks whose boundaries depend on the data
+//! itself rather than on fixed offsets. A boundary is placed after any byte at
+//! which the CRC32 of the preceding window of bytes has all bits of a mask
+//! cleared, so that inserting or removing data only changes the chunks around
+//! the edit. This is what makes it useful for deduplication.
+//!
+//! ```rust
+//! use crc32fast::chunker::{Chunker, ChunkerParams};
+//!
+//! let data = vec![0x55; 100_000];
+//! let chunker = Chunker::new(ChunkerParams::new(1024, 4096, 16384));
+//! for chunk in chunker.chunks(&data) {
+//!     assert_eq!(chunk.crc, crc32fast::hash(chunk.data));
+//! }
+//! ```
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::RollingHasher;
+
+/// The parameters of a [`Chunker`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ChunkerParams {
+    /// The minimum size of a chunk, except for the last one.
+    pub min_size: usize,
+    /// The maximum size of a chunk.
+    pub max_size: usize,
+    /// The bits of the fingerprint that must be zero at a chunk boundary.
+    pub mask: u32,
+    /// The length of the window over which the fingerprint is computed. It
+    /// must not be larger than `min_size`.
+    pub window_len: usize,
+}
+
+/// The parameters used by restic, with chunks of 512 KiB to 8 MiB, and about
+/// 1 MiB on average.
+pub const DEFAULT: ChunkerParams = ChunkerParams::new(512 * 1024, 1024 * 1024, 8 * 1024 * 1024);
+
+impl ChunkerParams {
+    /// Create parameters for chunks of the given minimum, average and maximum
+    /// sizes, with a window of 64 bytes.
+    ///
+    /// The mask is chosen such that a boundary is found on average `avg_size
+    /// - min_size` bytes after the minimum size, rounded down to a power of
+    /// two. The average is not exact, as some chunks are cut at `max_size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sizes are not in order, or if `min_size` is smaller than
+    /// the window.
+    pub const fn new(min_size: usize, avg_size: usize, max_size: usize) -> Self {
+        const WINDOW_LEN: usize = 64;
+        assert!(min_size <= avg_size && avg_size <= max_size);
+        assert!(WINDOW_LEN <= min_size);
+
+        let spread = avg_size - min_size;
+        let bits = if spread == 0 {
+            0
+        } else {
+            usize::BITS - 1 - spread.leading_zeros()
+        };
+        let mask = if bits >= 32 { !0 } else { (1 << bits) - 1 };
+
+        ChunkerParams {
+            min_size,
+            max_size,
+            mask,
+            window_len: WINDOW_LEN,
+        }
+    }
+}
+
+/// A chunk of data, together with its position and CRC32.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Chunk<'a> {
+    /// The offset of the chunk from the start of the data.
+    pub offset: u64,
+    /// The contents of the chunk.
+    pub data: &'a [u8],
+    /// The CRC32 of the contents.
+    pub crc: u32,
+}
+
+/// An owned chunk of data, as produced when chunking an [`io::Read`].
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwnedChunk {
+    /// The offset of the chunk from the start of the data.
+    pub offset: u64,
+    /// The contents of the chunk.
+    pub data: Vec<u8>,
+    /// The CRC32 of the contents.
+    pub crc: u32,
+}
+
+/// Splits data into content-defined chunks.
+#[derive(Clone)]
+pub struct Chunker {
+    params: ChunkerParams,
+    rolling: RollingHasher,
+}
+
+impl Chunker {
+    /// Create a new `Chunker` with the given parameters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `params.min_size` is zero, larger than `params.max_size`, or
+    /// smaller than `params.window_len`.
+    pub fn new(params: ChunkerParams) -> Self {
+        assert!(params.min_size > 0);
+        assert!(params.min_size <= params.max_size);
+        assert!(params.window_len <= params.min_size);
+        Chunker {
+            params,
+            rolling: RollingHasher::new(params.window_len as u64),
+        }
+    }
+
+    /// Returns the parameters of this chunker.
+    pub fn params(&self) -> &ChunkerParams {
+        &self.params
+    }
+
+    /// Returns an iterator over the chunks of a byte slice.
+    pub fn chunks<'a>(&self, buf: &'a [u8]) -> Chunks<'a> {
+        Chunks {
+            chunker: self.clone(),
+            buf,
+            offset: 0,
+        }
+    }
+
+    /// Returns an iterator over the chunks of the data read from `reader`.
+    ///
+    /// At most twice `max_size` bytes of data are buffered at a time.
+    #[cfg(feature = "std")]
+    pub fn chunk_reader<R: io::Read>(&self, reader: R) -> ReadChunks<R> {
+        ReadChunks {
+            chunker: self.clone(),
+            reader,
+            buf: Vec::new(),
+            start: 0,
+            end: 0,
+            offset: 0,
+            eof: false,
+        }
+    }
+
+    // Returns the length of the chunk at the start of `buf`, assuming that the
+    // data ends after `buf` if it is shorter than `max_size`.
+    fn cut(&mut self, buf: &[u8]) -> usize {
+        let ChunkerParams {
+            min_size,
+            max_size,
+            mask,
+            window_len,
+        } = self.params;
+        if buf.len() <= min_size {
+            return buf.len();
+        }
+
+        self.rolling.reset();
+        self.rolling.update(&buf[min_size - window_len..min_size]);
+        if self.rolling.crc() & mask == 0 {
+            return min_size;
+        }
+
+        let end = buf.len().min(max_size);
+        for i in min_size..end {
+            if self.rolling.roll(buf[i - window_len], buf[i]) & mask == 0 {
+                return i + 1;
+            }
+        }
+        end
+    }
+}
+
+impl fmt::Debug for Chunker {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("crc32fast::chunker::Chunker")
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+impl Default for Chunker {
+    fn default() -> Self {
+        Self::new(DEFAULT)
+    }
+}
+
+/// An iterator over the chunks of a byte slice.
+///
+/// This is created by [`Chunker::chunks`].
+#[derive(Clone, Debug)]
+pub struct Chunks<'a> {
+    chunker: Chunker,
+    buf: &'a [u8],
+    offset: u64,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = Chunk<'a>;
+
+    fn next(&mut self) -> Option<Chunk<'a>> {
+        if self.buf.is_empty() {
+            return None;
+        }
+
+        let len = self.chunker.cut(self.buf);
+        let (data, rest) = self.buf.split_at(len);
+        let chunk = Chunk {
+            offset: self.offset,
+            data,
+            crc: crate::hash(data),
+        };
+        self.buf = rest;
+        self.offset += len as u64;
+        Some(chunk)
+    }
+}
+
+/// An iterator over the chunks of the data read from an [`io::Read`].
+///
+/// This is created by [`Chunker::chunk_reader`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct ReadChunks<R> {
+    chunker: Chunker,
+    reader: R,
+    // Sized to twice `max_size` on the first read, with the data not yet
+    // handed out in `buf[start..end]`.
+    buf: Vec<u8>,
+    start: usize,
+    end: usize,
+    offset: u64,
+    eof: bool,
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read> ReadChunks<R> {
+    // Reads until `max_size` bytes are buffered or the reader is exhausted.
+    fn fill(&mut self) -> io::Result<()> {
+        let max_size = self.chunker.params.max_size;
+        if self.buf.is_empty() {
+            self.buf.resize(2 * max_size, 0);
+        }
+        // Only move the data to the front once the rest of the buffer can't
+        // hold a whole chunk, so that each byte is copied at most once.
+        if self.buf.len() - self.start < max_size && !self.eof {
+            self.buf.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+
+        while !self.eof && self.end - self.start < max_size {
+            match self.reader.read(&mut self.buf[self.end..]) {
+                Ok(0) => self.eof = true,
+                Ok(n) => self.end += n,
+                Err(e) => {
+                    if e.kind() != io::ErrorKind::Interrupted {
+                        return Err(e);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Read> Iterator for ReadChunks<R> {
+    type Item = io::Result<OwnedChunk>;
+
+    fn next(&mut self) -> Option<io::Result<OwnedChunk>> {
+        if let Err(e) = self.fill() {
+            return Some(Err(e));
+        }
+        if self.start == self.end {
+            return None;
+        }
+
+        let len = self.chunker.cut(&self.buf[self.start..self.end]);
+        let data = self.buf[self.start..self.start + len].to_vec();
+        self.start += len;
+        let chunk = OwnedChunk {
+            offset: self.offset,
+            crc: crate::hash(&data),
+            data,
+        };
+        self.offset += len as u64;
+        Some(Ok(chunk))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Chunker, ChunkerParams};
+
+    fn params() -> ChunkerParams {
+        ChunkerParams {
+            window_len: 8,
+            ..ChunkerParams::new(64, 96, 256)
+        }
+    }
+
+    #[test]
+    fn new_params() {
+        let params = ChunkerParams::new(512 * 1024, 1024 * 1024, 8 * 1024 * 1024);
+        assert_eq!(params.mask, (1 << 19) - 1);
+        assert_eq!(ChunkerParams::new(64, 64, 64).mask, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_min_size() {
+        Chunker::new(ChunkerParams {
+            min_size: 0,
+            window_len: 0,
+            ..params()
+        });
+    }
+
+    quickcheck::quickcheck! {
+        fn chunks(data: Vec<u8>, seed: u64) -> bool {
+            // Stretch the data, as quickcheck only generates short vectors.
+            let data: Vec<u8> = data
+                .iter()
+                .cycle()
+                .take(data.len() * 16)
+                .enumerate()
+                .map(|(i, b)| b ^ (seed >> (i % 8 * 8)) as u8 ^ i as u8)
+                .collect();
+            let chunker = Chunker::new(params());
+
+            let mut offset = 0;
+            let mut chunks = chunker.chunks(&data).peekable();
+            while let Some(chunk) = chunks.next() {
+                let len = chunk.data.len();
+                let last = chunks.peek().is_none();
+                if chunk.offset != offset as u64
+                    || chunk.data != &data[offset..offset + len]
+                    || chunk.crc != crate::hash(chunk.data)
+                    || len > 256
+                    || (len < 64 && !last)
+                {
+                    return false;
+                }
+                offset += len;
+            }
+            offset == data.len()
+        }
+
+        fn chunk_reader(data: Vec<u8>, read_len: u8) -> bool {
+            // Return short reads, to check that chunks don't depend on them.
+            struct ShortReader<'a>(&'a [u8], usize);
+            impl std::io::Read for ShortReader<'_> {
+                fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                    let len = buf.len().min(self.1).min(self.0.len());
+                    buf[..len].copy_from_slice(&self.0[..len]);
+                    self.0 = &self.0[len..];
+                    Ok(len)
+                }
+            }
+
+            let data = data.repeat(8);
+            let chunker = Chunker::new(params());
+            let reader = ShortReader(&data, usize::from(read_len).max(1));
+            let owned = chunker.chunk_reader(reader).map(Result::unwrap);
+            owned.eq(chunker.chunks(&data).map(|chunk| super::OwnedChunk {
+                offset: chunk.offset,
+                data: chunk.data.to_vec(),
+                crc: chunk.crc,
+            }))
+        }
+    }
+
+    #[test]
+    fn resynchronizes() {
+        let mut data = vec![0u8; 1 << 16];
+        let mut x = 1u32;
+        for b in data.iter_mut() {
+            x = x.wrapping_mul(1103515245).wrapping_add(12345);
+            *b = (x >> 16) as u8;
+        }
+        let chunker = Chunker::new(params());
+        let boundaries = |data: &[u8], skip: usize| {
+            chunker
+                .chunks(data)
+                .map(|chunk| chunk.offset as usize + chunk.data.len() - skip)
+                .collect::<std::collections::HashSet<_>>()
+        };
+
+        // Inserting data at the front only changes the first few chunks.
+        let shifted = [&[1, 2, 3][..], &data].concat();
+        let a = boundaries(&data, 0);
+        let b = boundaries(&shifted, 3);
+        assert!(a.intersection(&b).count() > a.len() * 9 / 10);
+    }
+}
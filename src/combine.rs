@@ -1,26 +1,194 @@
 const POLY: u32 = 0xedb88320;
 
-static X2N_TABLE: [u32; 32] = [
+const X2N_TABLE: [u32; 32] = [
     0x00800000, 0x00008000, 0xedb88320, 0xb1e6b092, 0xa06a2517, 0xed627dae, 0x88d14467, 0xd7bbfe6a,
     0xec447f11, 0x8e7ea170, 0x6427800e, 0x4d47bae0, 0x09fe548f, 0x83852d0f, 0x30362f1a, 0x7b5a9cc3,
     0x31fec169, 0x9fec022a, 0x6c8dedc4, 0x15d6874d, 0x5fde7a4e, 0xbad90e37, 0x2e4e5eef, 0x4eaba214,
     0xa8a472c0, 0x429a969e, 0x148d302a, 0xc40ba6d0, 0xc4e22c3c, 0x40000000, 0x20000000, 0x08000000,
 ];
 
+// The multiplicative inverses of the entries of `X2N_TABLE`, that is
+// x^(-8*2^n) modulo p(x), which undo appending 2^n zero bytes.
+const X2N_INV_TABLE: [u32; 32] = [
+    0x6567cb95, 0xd7125358, 0x5b358fd3, 0x2e9bb40b, 0x12a59a49, 0x8df9403d, 0x5139de12, 0xba340226,
+    0x29c45641, 0x12fbc105, 0xecd30c55, 0x3755ebd8, 0x24ee460c, 0x23783fcf, 0x479933fc, 0xa39442a5,
+    0x9ea0056d, 0xf42608f6, 0x20cacf04, 0x2a0cf83d, 0xeffd8645, 0x2a39a67d, 0x640ebd82, 0x9dfd8792,
+    0x277402ab, 0xad31bc4f, 0x31536354, 0x5ea35fca, 0x52b55e39, 0xdb710641, 0x6d930ac3, 0x6d3d2d4d,
+];
+
 // Calculates a(x) multiplied by b(x) modulo p(x), where p(x) is the CRC polynomial,
 // reflected. For speed, this requires that a not be zero.
-fn multiply(a: u32, mut b: u32) -> u32 {
+pub(crate) const fn multiply(a: u32, mut b: u32, poly: u32) -> u32 {
     let mut p = 0u32;
 
-    for i in 0..32 {
+    let mut i = 0;
+    while i < 32 {
         p ^= b & ((a >> (31 - i)) & 1).wrapping_neg();
-        b = (b >> 1) ^ ((b & 1).wrapping_neg() & POLY);
+        b = (b >> 1) ^ ((b & 1).wrapping_neg() & poly);
+        i += 1;
     }
 
     p
 }
 
-pub(crate) fn combine(crc1: u32, crc2: u32, len2: u64) -> u32 {
+// Builds a table of x^(k*2^n) modulo p(x), for all 64 bits of an exponent,
+// given x^k and p(x) reflected.
+pub(crate) const fn make_x2n_table(xk: u32, poly: u32) -> [u32; 64] {
+    let mut table = [0u32; 64];
+    let mut p = xk;
+    let mut n = 0;
+    while n < 64 {
+        table[n] = p;
+        p = multiply(p, p, poly);
+        n += 1;
+    }
+    table
+}
+
+/// A precomputed operator for combining CRC32 values, for a fixed length of the
+/// second block.
+///
+/// [`combine()`](crate::combine()) has to compute the effect of appending
+/// `len2` zero bytes anew on every call, which takes up to 64 polynomial
+/// multiplications. A `CombineOp` does that work once, after which each
+/// combination takes a single multiplication.
+///
+/// ```rust
+/// use crc32fast::CombineOp;
+///
+/// let op = CombineOp::new(7);
+/// let crc1 = crc32fast::hash(b"foo ");
+/// let crc2 = crc32fast::hash(b"bar baz");
+/// assert_eq!(op.apply(crc1, crc2), crc32fast::hash(b"foo bar baz"));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CombineOp {
+    len: u64,
+    // x^(8*len) modulo p(x), reflected.
+    mult: u32,
+}
+
+impl CombineOp {
+    /// Create a new operator for combining with blocks of `len` bytes.
+    pub const fn new(len: u64) -> Self {
+        CombineOp {
+            len,
+            mult: x8n(len, POLY, &X2N_TABLE),
+        }
+    }
+
+    /// Returns the length (in bytes) of the second block this operator was
+    /// created for.
+    pub const fn len2(&self) -> u64 {
+        self.len
+    }
+
+    /// Computes the CRC32 of `a || b` from `crc1 = crc(a)` and `crc2 = crc(b)`,
+    /// where `b` has the length this operator was created for.
+    ///
+    /// This gives the same result as `crc32fast::combine(crc1, crc2, len)`.
+    pub const fn apply(&self, crc1: u32, crc2: u32) -> u32 {
+        // Same special case as in `combine_with`.
+        if self.len == 0 {
+            return crc1;
+        }
+        multiply(self.mult, crc1, POLY) ^ crc2
+    }
+
+    // Computes the CRC32 of `a || 0^len` from `crc = crc(a)`, where `0^len`
+    // are as many zero bytes as this operator was created for.
+    pub(crate) const fn append_zeros(&self, crc: u32) -> u32 {
+        !multiply(self.mult, !crc, POLY)
+    }
+
+    // Returns the operator for blocks twice as long as this one was created
+    // for.
+    pub(crate) const fn square(&self) -> Self {
+        CombineOp {
+            len: self.len * 2,
+            mult: multiply(self.mult, self.mult, POLY),
+        }
+    }
+}
+
+// The specialized states have a faster implementation of `multiply` for `POLY`
+// on these targets.
+#[cfg(any(
+    all(
+        target_feature = "sse2",
+        any(target_arch = "x86", target_arch = "x86_64")
+    ),
+    all(stable_arm_crc32_intrinsics, target_arch = "aarch64")
+))]
+impl CombineOp {
+    // Like `append_zeros`, but with a faster implementation of `multiply` for
+    // `POLY`.
+    pub(crate) fn append_zeros_with<F>(&self, crc: u32, multiply: F) -> u32
+    where
+        F: FnOnce(u32, u32) -> u32,
+    {
+        !multiply(self.mult, !crc)
+    }
+
+    // Like `new`, but with a faster implementation of `multiply` for `POLY`.
+    pub(crate) fn new_with<F>(len: u64, multiply: F) -> Self
+    where
+        F: Fn(u32, u32) -> u32,
+    {
+        let mut mult = 1 << 31;
+        let n = 64 - len.leading_zeros();
+        for i in 0..n {
+            if (len >> i & 1) != 0 {
+                mult = multiply(X2N_TABLE[i as usize % X2N_TABLE.len()], mult);
+            }
+        }
+        CombineOp { len, mult }
+    }
+
+    // Like `apply`, but with a faster implementation of `multiply` for `POLY`.
+    pub(crate) fn apply_with<F>(&self, crc1: u32, crc2: u32, multiply: F) -> u32
+    where
+        F: FnOnce(u32, u32) -> u32,
+    {
+        if self.len == 0 {
+            return crc1;
+        }
+        multiply(self.mult, crc1) ^ crc2
+    }
+}
+
+pub(crate) const fn combine(crc1: u32, crc2: u32, len2: u64) -> u32 {
+    combine_with(crc1, crc2, len2, POLY, &X2N_TABLE)
+}
+
+// Calculates crc(b) from crc(a || b), crc(a) and len(b).
+pub(crate) const fn strip_prefix(crc: u32, crc1: u32, len2: u64) -> u32 {
+    // This is `combine` solved for `crc2`.
+    crc ^ multiply(x8n(len2, POLY, &X2N_TABLE), crc1, POLY)
+}
+
+// Calculates crc(a) from crc(a || b), crc(b) and len(b).
+pub(crate) const fn strip_suffix(crc: u32, crc2: u32, len2: u64) -> u32 {
+    // This is `combine` solved for `crc1`.
+    unshift(crc ^ crc2, len2)
+}
+
+// Calculates crc(x) multiplied by x^(-8*len) modulo p(x), undoing what
+// appending len zero bytes does to a CRC without the final inversion.
+pub(crate) const fn unshift(crc: u32, len: u64) -> u32 {
+    multiply(x8n(len, POLY, &X2N_INV_TABLE), crc, POLY)
+}
+
+// Generic version of `combine` for any reflected 32-bit polynomial. The table
+// holds x^(8*2^n) modulo p(x) for n = 0, 1, ..., and must cover a full cycle of
+// repeated squaring, so that lengths beyond 2^32 bytes wrap around correctly.
+pub(crate) const fn combine_with(
+    crc1: u32,
+    crc2: u32,
+    len2: u64,
+    poly: u32,
+    x2n_table: &[u32],
+) -> u32 {
     // Special case: If the length of the second chunk is zero, return the hash
     // of the first chunk.
     if len2 == 0 {
@@ -31,15 +199,33 @@ pub(crate) fn combine(crc1: u32, crc2: u32, len2: u64) -> u32 {
     // this is done in powers-of-two via a lookup table rather than one by one.
     let mut p = crc1;
     let n = 64 - len2.leading_zeros();
-    for i in 0..n {
+    let mut i = 0;
+    while i < n {
         if (len2 >> i & 1) != 0 {
-            p = multiply(X2N_TABLE[(i & 0x1F) as usize], p);
+            p = multiply(x2n_table[i as usize % x2n_table.len()], p, poly);
         }
+        i += 1;
     }
 
     p ^ crc2
 }
 
+// Calculates x^(8*len) modulo p(x), reflected, given a table as for
+// `combine_with`.
+const fn x8n(len: u64, poly: u32, x2n_table: &[u32]) -> u32 {
+    // x^0
+    let mut p = 1 << 31;
+    let n = 64 - len.leading_zeros();
+    let mut i = 0;
+    while i < n {
+        if (len >> i & 1) != 0 {
+            p = multiply(x2n_table[i as usize % x2n_table.len()], p, poly);
+        }
+        i += 1;
+    }
+    p
+}
+
 #[test]
 fn golden() {
     assert_eq!(combine(0x0, 0x1, 0x0), 0x0);
@@ -53,3 +239,28 @@ fn golden() {
         0xef922dda
     );
 }
+
+#[test]
+fn inverse_table() {
+    for (&x, &x_inv) in X2N_TABLE.iter().zip(X2N_INV_TABLE.iter()) {
+        assert_eq!(multiply(x, x_inv, POLY), 1 << 31);
+    }
+}
+
+#[cfg(test)]
+quickcheck::quickcheck! {
+    fn combine_op(crc1: u32, crc2: u32, len2: u64) -> bool {
+        CombineOp::new(len2).apply(crc1, crc2) == combine(crc1, crc2, len2)
+    }
+
+    fn strip_prefix_round_trip(crc1: u32, crc2: u32, len2: u64) -> bool {
+        // `combine` ignores `crc2` for empty blocks, whose CRC32 is always 0.
+        let crc2 = if len2 == 0 { 0 } else { crc2 };
+        strip_prefix(combine(crc1, crc2, len2), crc1, len2) == crc2
+    }
+
+    fn strip_suffix_round_trip(crc1: u32, crc2: u32, len2: u64) -> bool {
+        let crc2 = if len2 == 0 { 0 } else { crc2 };
+        strip_suffix(combine(crc1, crc2, len2), crc2, len2) == crc1
+    }
+}
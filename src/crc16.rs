@@ -0,0 +1,198 @@
+//! CRC-16 checksum computation.
+//!
+//! CRC-16 algorithms come in many flavours, which differ in their polynomial,
+//! initial value, bit order and final xor value. The most common ones are
+//! available as ready-to-use [`Crc16`] engines, named after their entries in
+//! the [CRC catalogue]:
+//!
+//! ```rust
+//! use crc32fast::crc16;
+//!
+//! assert_eq!(crc16::MODBUS.checksum(b"123456789"), 0x4b37);
+//!
+//! let mut hasher = crc16::T10_DIF.hasher();
+//! hasher.update(b"123456789");
+//! assert_eq!(hasher.finalize(), 0xd0db);
+//! ```
+//!
+//! The engines are [`SmallCrc`]s with eight lookup tables, so any other CRC-16
+//! can be computed by creating a [`Crc16`] from its [`SmallCrcParams`].
+//! Non-reflected algorithms such as CRC-16/T10-DIF are accelerated with
+//! carry-less multiplication where the CPU supports it.
+//!
+//! [CRC catalogue]: https://reveng.sourceforge.io/crc-catalogue/16.htm
+
+use crate::small::{SmallCrc, SmallCrcParams};
+
+/// A CRC-16 engine, processing eight bytes at a time.
+pub type Crc16 = SmallCrc<8>;
+
+/// Represents an in-progress CRC-16 computation.
+pub type Hasher<'a> = crate::small::Hasher<'a, 8>;
+
+/// CRC-16/IBM-3740, also known as CRC-16/CCITT-FALSE and CRC-16/AUTOSAR.
+pub const CRC_16_IBM_3740: SmallCrcParams = SmallCrcParams {
+    width: 16,
+    poly: 0x1021,
+    init: 0xffff,
+    refin: false,
+    refout: false,
+    xorout: 0x0000,
+};
+
+/// CRC-16/KERMIT, also known as CRC-16/CCITT and CRC-16/CCITT-TRUE.
+pub const CRC_16_KERMIT: SmallCrcParams = SmallCrcParams {
+    width: 16,
+    poly: 0x1021,
+    init: 0x0000,
+    refin: true,
+    refout: true,
+    xorout: 0x0000,
+};
+
+/// CRC-16/IBM-SDLC, also known as CRC-16/X-25, as used by X.25 and HDLC.
+pub const CRC_16_IBM_SDLC: SmallCrcParams = SmallCrcParams {
+    width: 16,
+    poly: 0x1021,
+    init: 0xffff,
+    refin: true,
+    refout: true,
+    xorout: 0xffff,
+};
+
+/// CRC-16/XMODEM, also known as CRC-16/ACORN and CRC-16/LTE.
+pub const CRC_16_XMODEM: SmallCrcParams = SmallCrcParams {
+    width: 16,
+    poly: 0x1021,
+    init: 0x0000,
+    refin: false,
+    refout: false,
+    xorout: 0x0000,
+};
+
+/// CRC-16/GENIBUS, also known as CRC-16/EPC and CRC-16/DARC.
+pub const CRC_16_GENIBUS: SmallCrcParams = SmallCrcParams {
+    width: 16,
+    poly: 0x1021,
+    init: 0xffff,
+    refin: false,
+    refout: false,
+    xorout: 0xffff,
+};
+
+/// CRC-16/MODBUS, as used by the Modbus RTU protocol.
+pub const CRC_16_MODBUS: SmallCrcParams = SmallCrcParams {
+    width: 16,
+    poly: 0x8005,
+    init: 0xffff,
+    refin: true,
+    refout: true,
+    xorout: 0x0000,
+};
+
+/// CRC-16/ARC, also known as CRC-16/LHA and CRC-IBM.
+pub const CRC_16_ARC: SmallCrcParams = SmallCrcParams {
+    width: 16,
+    poly: 0x8005,
+    init: 0x0000,
+    refin: true,
+    refout: true,
+    xorout: 0x0000,
+};
+
+/// CRC-16/USB, as used by USB data packets.
+pub const CRC_16_USB: SmallCrcParams = SmallCrcParams {
+    width: 16,
+    poly: 0x8005,
+    init: 0xffff,
+    refin: true,
+    refout: true,
+    xorout: 0xffff,
+};
+
+/// CRC-16/T10-DIF, as used by the T10 data integrity field of SCSI sectors.
+pub const CRC_16_T10_DIF: SmallCrcParams = SmallCrcParams {
+    width: 16,
+    poly: 0x8bb7,
+    init: 0x0000,
+    refin: false,
+    refout: false,
+    xorout: 0x0000,
+};
+
+/// CRC-16/DNP, as used by the DNP3 protocol.
+pub const CRC_16_DNP: SmallCrcParams = SmallCrcParams {
+    width: 16,
+    poly: 0x3d65,
+    init: 0x0000,
+    refin: true,
+    refout: true,
+    xorout: 0xffff,
+};
+
+/// All named presets in this module, keyed by their catalogue name.
+pub const CATALOG: &[(&str, SmallCrcParams)] = &[
+    ("CRC-16/IBM-3740", CRC_16_IBM_3740),
+    ("CRC-16/KERMIT", CRC_16_KERMIT),
+    ("CRC-16/IBM-SDLC", CRC_16_IBM_SDLC),
+    ("CRC-16/XMODEM", CRC_16_XMODEM),
+    ("CRC-16/GENIBUS", CRC_16_GENIBUS),
+    ("CRC-16/MODBUS", CRC_16_MODBUS),
+    ("CRC-16/ARC", CRC_16_ARC),
+    ("CRC-16/USB", CRC_16_USB),
+    ("CRC-16/T10-DIF", CRC_16_T10_DIF),
+    ("CRC-16/DNP", CRC_16_DNP),
+];
+
+/// CRC-16/IBM-3740 engine.
+pub static IBM_3740: Crc16 = Crc16::new(CRC_16_IBM_3740);
+/// CRC-16/KERMIT engine.
+pub static KERMIT: Crc16 = Crc16::new(CRC_16_KERMIT);
+/// CRC-16/IBM-SDLC (X.25) engine.
+pub static IBM_SDLC: Crc16 = Crc16::new(CRC_16_IBM_SDLC);
+/// CRC-16/XMODEM engine.
+pub static XMODEM: Crc16 = Crc16::new(CRC_16_XMODEM);
+/// CRC-16/GENIBUS engine.
+pub static GENIBUS: Crc16 = Crc16::new(CRC_16_GENIBUS);
+/// CRC-16/MODBUS engine.
+pub static MODBUS: Crc16 = Crc16::new(CRC_16_MODBUS);
+/// CRC-16/ARC engine.
+pub static ARC: Crc16 = Crc16::new(CRC_16_ARC);
+/// CRC-16/USB engine.
+pub static USB: Crc16 = Crc16::new(CRC_16_USB);
+/// CRC-16/T10-DIF engine.
+pub static T10_DIF: Crc16 = Crc16::new(CRC_16_T10_DIF);
+/// CRC-16/DNP engine.
+pub static DNP: Crc16 = Crc16::new(CRC_16_DNP);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check() {
+        assert_eq!(IBM_3740.checksum(b"123456789"), 0x29b1);
+        assert_eq!(KERMIT.checksum(b"123456789"), 0x2189);
+        assert_eq!(IBM_SDLC.checksum(b"123456789"), 0x906e);
+        assert_eq!(XMODEM.checksum(b"123456789"), 0x31c3);
+        assert_eq!(GENIBUS.checksum(b"123456789"), 0xd64e);
+        assert_eq!(MODBUS.checksum(b"123456789"), 0x4b37);
+        assert_eq!(ARC.checksum(b"123456789"), 0xbb3d);
+        assert_eq!(USB.checksum(b"123456789"), 0xb4c8);
+        assert_eq!(T10_DIF.checksum(b"123456789"), 0xd0db);
+        assert_eq!(DNP.checksum(b"123456789"), 0xea82);
+    }
+
+    #[test]
+    fn catalog() {
+        assert_eq!(CATALOG.len(), 10);
+        for &(_, params) in CATALOG {
+            assert_eq!(params.width, 16);
+            let crc = Crc16::new(params);
+            let mut hasher = crc.hasher();
+            hasher.update(b"12345");
+            hasher.update(b"6789");
+            assert_eq!(hasher.finalize(), crc.checksum(b"123456789"));
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! CRC-24 checksum computation.
+//!
+//! Two CRC-24 algorithms are in common use, with different polynomials and
+//! bit orders:
+//!
+//! - CRC-24/OPENPGP, the checksum of OpenPGP ASCII armor (RFC 4880)
+//! - CRC-24/BLE, the checksum of Bluetooth Low Energy link-layer packets,
+//!   whose initial value is negotiated per connection
+//!
+//! ```rust
+//! use crc32fast::crc24;
+//!
+//! assert_eq!(crc24::OPENPGP.checksum(b"123456789"), 0x21cf02);
+//!
+//! // BLE data channels use the CRCInit value from the connection request
+//! let mut hasher = crc24::Hasher::new_with_init(&crc24::BLE, 0x555555);
+//! hasher.update(b"123456789");
+//! assert_eq!(hasher.finalize(), 0xc25a56);
+//! ```
+//!
+//! The engines are [`SmallCrc`]s with a single lookup table.
+
+use crate::small::{SmallCrc, SmallCrcParams};
+
+/// A CRC-24 engine.
+pub type Crc24 = SmallCrc;
+
+/// Represents an in-progress CRC-24 computation.
+pub type Hasher<'a> = crate::small::Hasher<'a>;
+
+/// CRC-24/OPENPGP, as specified by RFC 4880.
+pub const CRC_24_OPENPGP: SmallCrcParams = SmallCrcParams {
+    width: 24,
+    poly: 0x864cfb,
+    init: 0xb704ce,
+    refin: false,
+    refout: false,
+    xorout: 0x000000,
+};
+
+/// CRC-24/BLE, as specified by the Bluetooth Core Specification.
+///
+/// The initial value of `0x555555` is the one used on advertising channels.
+/// Data channels use a per-connection value instead, see
+/// [`Hasher::new_with_init`](crate::small::Hasher::new_with_init).
+pub const CRC_24_BLE: SmallCrcParams = SmallCrcParams {
+    width: 24,
+    poly: 0x00065b,
+    init: 0x555555,
+    refin: true,
+    refout: true,
+    xorout: 0x000000,
+};
+
+/// CRC-24/OPENPGP engine.
+pub static OPENPGP: Crc24 = Crc24::new(CRC_24_OPENPGP);
+/// CRC-24/BLE engine.
+pub static BLE: Crc24 = Crc24::new(CRC_24_BLE);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check() {
+        assert_eq!(OPENPGP.checksum(b"123456789"), 0x21cf02);
+        assert_eq!(OPENPGP.checksum(b"hello world"), 0xb03cb7);
+        assert_eq!(BLE.checksum(b"123456789"), 0xc25a56);
+
+        let mut hasher = Hasher::new_with_init(&BLE, 0x123456);
+        hasher.update(b"123456789");
+        assert_eq!(hasher.finalize(), 0x452627);
+    }
+}
@@ -0,0 +1,61 @@
+use super::table::CRC32C_TABLE;
+
+#[derive(Clone)]
+pub struct State {
+    state: u32,
+}
+
+impl State {
+    pub fn new(state: u32) -> Self {
+        State { state }
+    }
+
+    pub fn update(&mut self, buf: &[u8]) {
+        self.state = update_fast_16(self.state, buf);
+    }
+
+    pub fn finalize(self) -> u32 {
+        self.state
+    }
+
+    pub fn reset(&mut self) {
+        self.state = 0;
+    }
+
+    pub fn combine(&mut self, other: u32, amount: u64) {
+        self.state = super::combine::combine(self.state, other, amount);
+    }
+}
+
+pub(crate) fn update_fast_16(prev: u32, buf: &[u8]) -> u32 {
+    crate::baseline::update_fast_16_with(&CRC32C_TABLE, prev, buf)
+}
+
+#[cfg(test)]
+pub(crate) fn update_slow(prev: u32, buf: &[u8]) -> u32 {
+    crate::baseline::update_slow_with(&CRC32C_TABLE, prev, buf)
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn slow() {
+        assert_eq!(super::update_slow(0, b""), 0);
+        assert_eq!(super::update_slow(0, b"123456789"), 0xe3069283);
+        assert_eq!(super::update_slow(0, b"hello world"), 0xc99465aa);
+        assert_eq!(super::update_slow(0, b"hello"), 0x9a71bb4c);
+        assert_eq!(super::update_slow(0x9a71bb4c, b" world"), 0xc99465aa);
+
+        // test vectors from RFC 3720, appendix B.4
+        assert_eq!(super::update_slow(0, &[0x00; 32]), 0x8a9136aa);
+        assert_eq!(super::update_slow(0, &[0xff; 32]), 0x62a8ab43);
+        assert_eq!(super::update_slow(0, b"\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x0A\x0B\x0C\x0D\x0E\x0F\x10\x11\x12\x13\x14\x15\x16\x17\x18\x19\x1A\x1B\x1C\x1D\x1E\x1F"), 0x46dd794e);
+        assert_eq!(super::update_slow(0, b"\x1F\x1E\x1D\x1C\x1B\x1A\x19\x18\x17\x16\x15\x14\x13\x12\x11\x10\x0F\x0E\x0D\x0C\x0B\x0A\x09\x08\x07\x06\x05\x04\x03\x02\x01\x00"), 0x113fdb5c);
+    }
+
+    quickcheck::quickcheck! {
+        fn fast_16_is_the_same_as_slow(crc: u32, bytes: Vec<u8>) -> bool {
+            super::update_fast_16(crc, &bytes) == super::update_slow(crc, &bytes)
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! CRC-32C (Castagnoli) checksum computation.
+//!
+//! CRC-32C uses the polynomial `0x1EDC6F41` instead of the IEEE one, and is
+//! the checksum of choice for iSCSI, SCTP, ext4, btrfs and Kafka among others.
+//! The API mirrors the top-level one:
+//!
+//! ```rust
+//! use crc32fast::crc32c::Hasher;
+//!
+//! let mut hasher = Hasher::new();
+//! hasher.update(b"123456789");
+//! assert_eq!(hasher.finalize(), 0xe3069283);
+//! assert_eq!(crc32fast::crc32c::hash(b"123456789"), 0xe3069283);
+//! ```
+
+use core::fmt;
+use core::hash;
+
+pub(crate) mod baseline;
+pub(crate) mod combine;
+mod table;
+
+/// Computes the CRC-32C hash of a byte slice.
+///
+/// Check out [`Hasher`] for more advanced use-cases.
+pub fn hash(buf: &[u8]) -> u32 {
+    let mut h = Hasher::new();
+    h.update(buf);
+    h.finalize()
+}
+
+#[derive(Clone)]
+enum State {
+    Baseline(baseline::State),
+    Specialized(crate::specialized::Crc32cState),
+}
+
+#[derive(Clone)]
+/// Represents an in-progress CRC-32C computation.
+pub struct Hasher {
+    amount: u64,
+    state: State,
+}
+
+const DEFAULT_INIT_STATE: u32 = 0;
+
+impl Hasher {
+    /// Create a new `Hasher`.
+    ///
+    /// This will perform a CPU feature detection at runtime to select the most
+    /// optimal implementation for the current processor architecture.
+    pub fn new() -> Self {
+        Self::new_with_initial(DEFAULT_INIT_STATE)
+    }
+
+    /// Create a new `Hasher` with an initial CRC-32C state.
+    ///
+    /// This works just like `Hasher::new`, except that it allows for an initial
+    /// CRC-32C state to be passed in.
+    pub fn new_with_initial(init: u32) -> Self {
+        Self::new_with_initial_len(init, 0)
+    }
+
+    /// Create a new `Hasher` with an initial CRC-32C state.
+    ///
+    /// As `new_with_initial`, but also accepts a length (in bytes). The
+    /// resulting object can then be used with `combine` to compute `crc(a ||
+    /// b)` from `crc(a)`, `crc(b)`, and `len(b)`.
+    pub fn new_with_initial_len(init: u32, amount: u64) -> Self {
+        Self::internal_new_specialized(init, amount)
+            .unwrap_or_else(|| Self::internal_new_baseline(init, amount))
+    }
+
+    #[doc(hidden)]
+    // Internal-only API. Don't use.
+    pub fn internal_new_baseline(init: u32, amount: u64) -> Self {
+        Hasher {
+            amount,
+            state: State::Baseline(baseline::State::new(init)),
+        }
+    }
+
+    #[doc(hidden)]
+    // Internal-only API. Don't use.
+    pub fn internal_new_specialized(init: u32, amount: u64) -> Option<Self> {
+        {
+            if let Some(state) = crate::specialized::Crc32cState::new(init) {
+                return Some(Hasher {
+                    amount,
+                    state: State::Specialized(state),
+                });
+            }
+        }
+        None
+    }
+
+    /// Process the given byte slice and update the hash state.
+    pub fn update(&mut self, buf: &[u8]) {
+        self.amount += buf.len() as u64;
+        match self.state {
+            State::Baseline(ref mut state) => state.update(buf),
+            State::Specialized(ref mut state) => state.update(buf),
+        }
+    }
+
+    /// Finalize the hash state and return the computed CRC-32C value.
+    pub fn finalize(self) -> u32 {
+        match self.state {
+            State::Baseline(state) => state.finalize(),
+            State::Specialized(state) => state.finalize(),
+        }
+    }
+
+    /// Reset the hash state.
+    pub fn reset(&mut self) {
+        self.amount = 0;
+        match self.state {
+            State::Baseline(ref mut state) => state.reset(),
+            State::Specialized(ref mut state) => state.reset(),
+        }
+    }
+
+    /// Combine the hash state with the hash state for the subsequent block of bytes.
+    pub fn combine(&mut self, other: &Self) {
+        self.amount += other.amount;
+        let other_crc = other.clone().finalize();
+        match self.state {
+            State::Baseline(ref mut state) => state.combine(other_crc, other.amount),
+            State::Specialized(ref mut state) => state.combine(other_crc, other.amount),
+        }
+    }
+}
+
+impl fmt::Debug for Hasher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("crc32fast::crc32c::Hasher").finish()
+    }
+}
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl hash::Hasher for Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes)
+    }
+
+    fn finish(&self) -> u64 {
+        u64::from(self.clone().finalize())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Hasher;
+
+    quickcheck::quickcheck! {
+        fn combine(bytes_1: Vec<u8>, bytes_2: Vec<u8>) -> bool {
+            let mut hash_a = Hasher::new();
+            hash_a.update(&bytes_1);
+            hash_a.update(&bytes_2);
+            let mut hash_b = Hasher::new();
+            hash_b.update(&bytes_2);
+            let mut hash_c = Hasher::new();
+            hash_c.update(&bytes_1);
+            hash_c.combine(&hash_b);
+
+            hash_a.finalize() == hash_c.finalize()
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use super::params::Params;
+
+#[derive(Clone)]
+pub struct State {
+    state: u64,
+    params: &'static Params,
+}
+
+impl State {
+    pub fn new(state: u64, params: &'static Params) -> Self {
+        State { state, params }
+    }
+
+    pub fn update(&mut self, buf: &[u8]) {
+        self.state = update_fast_8(self.params, self.state, buf);
+    }
+
+    pub fn finalize(self) -> u64 {
+        self.state
+    }
+
+    pub fn reset(&mut self) {
+        self.state = 0;
+    }
+
+    pub fn combine(&mut self, other: u64, amount: u64) {
+        self.state = super::combine::combine(self.params, self.state, other, amount);
+    }
+}
+
+pub(crate) fn update_fast_8(params: &Params, prev: u64, mut buf: &[u8]) -> u64 {
+    const UNROLL: usize = 4;
+    const BYTES_AT_ONCE: usize = 8 * UNROLL;
+
+    let table = &params.table;
+    let mut crc = !prev;
+
+    while buf.len() >= BYTES_AT_ONCE {
+        for _ in 0..UNROLL {
+            let w0 = u64::from_le_bytes([
+                buf[0], buf[1], buf[2], buf[3], buf[4], buf[5], buf[6], buf[7],
+            ]) ^ crc;
+            crc = table[0x0][(w0 >> 56) as usize]
+                ^ table[0x1][((w0 >> 48) & 0xFF) as usize]
+                ^ table[0x2][((w0 >> 40) & 0xFF) as usize]
+                ^ table[0x3][((w0 >> 32) & 0xFF) as usize]
+                ^ table[0x4][((w0 >> 24) & 0xFF) as usize]
+                ^ table[0x5][((w0 >> 16) & 0xFF) as usize]
+                ^ table[0x6][((w0 >> 8) & 0xFF) as usize]
+                ^ table[0x7][(w0 & 0xFF) as usize];
+            buf = &buf[8..];
+        }
+    }
+
+    update_slow(params, !crc, buf)
+}
+
+pub(crate) fn update_slow(params: &Params, prev: u64, buf: &[u8]) -> u64 {
+    let mut crc = !prev;
+
+    for &byte in buf.iter() {
+        crc = params.table[0][((crc as u8) ^ byte) as usize] ^ (crc >> 8);
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::params::{NVME, XZ};
+
+    #[test]
+    fn slow() {
+        assert_eq!(super::update_slow(&XZ, 0, b""), 0);
+        assert_eq!(super::update_slow(&XZ, 0, b"123456789"), 0x995dc9bbdf1939fa);
+        assert_eq!(
+            super::update_slow(&XZ, 0, b"hello world"),
+            0x53037ecdef2352da
+        );
+        assert_eq!(super::update_slow(&XZ, 0, b"hello"), 0x9b1edae5dbb937b1);
+        assert_eq!(
+            super::update_slow(&XZ, 0x9b1edae5dbb937b1, b" world"),
+            0x53037ecdef2352da
+        );
+
+        assert_eq!(super::update_slow(&NVME, 0, b""), 0);
+        assert_eq!(
+            super::update_slow(&NVME, 0, b"123456789"),
+            0xae8b14860a799888
+        );
+        assert_eq!(
+            super::update_slow(&NVME, 0, b"hello world"),
+            0x8d29d5c3f6ea8ebe
+        );
+
+        // test vectors from the NVM Command Set specification
+        assert_eq!(
+            super::update_slow(&NVME, 0, &[0x00; 4096]),
+            0x6482d367eb22b64e
+        );
+        assert_eq!(
+            super::update_slow(&NVME, 0, &[0xff; 4096]),
+            0xc0ddba7302eca3ac
+        );
+    }
+
+    quickcheck::quickcheck! {
+        fn fast_8_is_the_same_as_slow(crc: u64, bytes: Vec<u8>) -> bool {
+            super::update_fast_8(&XZ, crc, &bytes) == super::update_slow(&XZ, crc, &bytes)
+                && super::update_fast_8(&NVME, crc, &bytes) == super::update_slow(&NVME, crc, &bytes)
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use super::params::Params;
+
+// Calculates a(x) multiplied by b(x) modulo p(x), where p(x) is the CRC polynomial,
+// reflected.
+pub(crate) const fn multiply(a: u64, mut b: u64, poly: u64) -> u64 {
+    let mut p = 0u64;
+
+    let mut i = 0;
+    while i < 64 {
+        p ^= b & ((a >> (63 - i)) & 1).wrapping_neg();
+        b = (b >> 1) ^ ((b & 1).wrapping_neg() & poly);
+        i += 1;
+    }
+
+    p
+}
+
+pub(crate) fn combine(params: &Params, crc1: u64, crc2: u64, len2: u64) -> u64 {
+    // Special case: If the length of the second chunk is zero, return the hash
+    // of the first chunk.
+    if len2 == 0 {
+        return crc1;
+    }
+
+    // We are padding the first checksum with len2-amount of zeroes. For efficiency,
+    // this is done in powers-of-two via a lookup table rather than one by one.
+    let mut p = crc1;
+    let n = 64 - len2.leading_zeros();
+    for i in 0..n {
+        if (len2 >> i & 1) != 0 {
+            p = multiply(params.x2n_table[i as usize], p, params.poly);
+        }
+    }
+
+    p ^ crc2
+}
+
+#[test]
+fn golden() {
+    use super::params::{NVME, XZ};
+
+    assert_eq!(combine(&XZ, 0x0, 0x1, 0x0), 0x0);
+    assert_eq!(combine(&XZ, 0xc401f8c9, 0x00000000, 0x0), 0xc401f8c9);
+    // crc("hello world") from crc("hello ") and crc("world")
+    assert_eq!(
+        combine(&XZ, 0x18ef05ba04afb00a, 0x3e10fcfa54e158f8, 0x5),
+        0x53037ecdef2352da
+    );
+    assert_eq!(
+        combine(&NVME, 0xc3430818b4596582, 0x34a6f418fb3758bf, 0x5),
+        0x8d29d5c3f6ea8ebe
+    );
+}
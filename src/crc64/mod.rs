@@ -0,0 +1,226 @@
+//! CRC-64 checksum computation.
+//!
+//! Two variants are supported, both of which use reflected bit order and an
+//! initial value and final xor of all ones:
+//!
+//! - CRC-64/XZ, using the ECMA-182 polynomial, as used by the `xz` format
+//! - CRC-64/NVME, as used by NVMe end-to-end data protection
+//!
+//! The API mirrors the top-level one, with [`Variant`] selecting the
+//! polynomial:
+//!
+//! ```rust
+//! use crc32fast::crc64::{Hasher, Variant};
+//!
+//! let mut hasher = Hasher::new();
+//! hasher.update(b"123456789");
+//! assert_eq!(hasher.finalize(), 0x995dc9bbdf1939fa);
+//!
+//! let mut hasher = Hasher::new_with_variant(Variant::Nvme, 0, 0);
+//! hasher.update(b"123456789");
+//! assert_eq!(hasher.finalize(), 0xae8b14860a799888);
+//! ```
+
+use core::fmt;
+use core::hash;
+
+pub(crate) mod baseline;
+pub(crate) mod combine;
+pub(crate) mod params;
+
+/// The CRC-64 variants supported by [`Hasher`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Variant {
+    /// CRC-64/XZ, also known as CRC-64/GO-ECMA.
+    #[default]
+    Xz,
+    /// CRC-64/NVME.
+    Nvme,
+}
+
+impl Variant {
+    fn params(self) -> &'static params::Params {
+        match self {
+            Variant::Xz => &params::XZ,
+            Variant::Nvme => &params::NVME,
+        }
+    }
+}
+
+/// Computes the CRC-64/XZ hash of a byte slice.
+///
+/// Check out [`Hasher`] for more advanced use-cases.
+pub fn hash(buf: &[u8]) -> u64 {
+    hash_with(Variant::Xz, buf)
+}
+
+/// Computes the CRC-64 hash of a byte slice, using the given variant.
+pub fn hash_with(variant: Variant, buf: &[u8]) -> u64 {
+    let mut h = Hasher::new_with_variant(variant, DEFAULT_INIT_STATE, 0);
+    h.update(buf);
+    h.finalize()
+}
+
+#[derive(Clone)]
+enum State {
+    Baseline(baseline::State),
+    Specialized(crate::specialized::Crc64State),
+}
+
+#[derive(Clone)]
+/// Represents an in-progress CRC-64 computation.
+pub struct Hasher {
+    amount: u64,
+    variant: Variant,
+    state: State,
+}
+
+const DEFAULT_INIT_STATE: u64 = 0;
+
+impl Hasher {
+    /// Create a new CRC-64/XZ `Hasher`.
+    ///
+    /// This will perform a CPU feature detection at runtime to select the most
+    /// optimal implementation for the current processor architecture.
+    pub fn new() -> Self {
+        Self::new_with_initial(DEFAULT_INIT_STATE)
+    }
+
+    /// Create a new CRC-64/XZ `Hasher` with an initial CRC-64 state.
+    ///
+    /// This works just like `Hasher::new`, except that it allows for an initial
+    /// CRC-64 state to be passed in.
+    pub fn new_with_initial(init: u64) -> Self {
+        Self::new_with_initial_len(init, 0)
+    }
+
+    /// Create a new CRC-64/XZ `Hasher` with an initial CRC-64 state.
+    ///
+    /// As `new_with_initial`, but also accepts a length (in bytes). The
+    /// resulting object can then be used with `combine` to compute `crc(a ||
+    /// b)` from `crc(a)`, `crc(b)`, and `len(b)`.
+    pub fn new_with_initial_len(init: u64, amount: u64) -> Self {
+        Self::new_with_variant(Variant::Xz, init, amount)
+    }
+
+    /// Create a new `Hasher` for the given variant, with an initial CRC-64
+    /// state and length (in bytes).
+    pub fn new_with_variant(variant: Variant, init: u64, amount: u64) -> Self {
+        Self::internal_new_specialized(variant, init, amount)
+            .unwrap_or_else(|| Self::internal_new_baseline(variant, init, amount))
+    }
+
+    #[doc(hidden)]
+    // Internal-only API. Don't use.
+    pub fn internal_new_baseline(variant: Variant, init: u64, amount: u64) -> Self {
+        Hasher {
+            amount,
+            variant,
+            state: State::Baseline(baseline::State::new(init, variant.params())),
+        }
+    }
+
+    #[doc(hidden)]
+    // Internal-only API. Don't use.
+    pub fn internal_new_specialized(variant: Variant, init: u64, amount: u64) -> Option<Self> {
+        {
+            if let Some(state) = crate::specialized::Crc64State::new(init, variant.params()) {
+                return Some(Hasher {
+                    amount,
+                    variant,
+                    state: State::Specialized(state),
+                });
+            }
+        }
+        None
+    }
+
+    /// Returns the variant computed by this `Hasher`.
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    /// Process the given byte slice and update the hash state.
+    pub fn update(&mut self, buf: &[u8]) {
+        self.amount += buf.len() as u64;
+        match self.state {
+            State::Baseline(ref mut state) => state.update(buf),
+            State::Specialized(ref mut state) => state.update(buf),
+        }
+    }
+
+    /// Finalize the hash state and return the computed CRC-64 value.
+    pub fn finalize(self) -> u64 {
+        match self.state {
+            State::Baseline(state) => state.finalize(),
+            State::Specialized(state) => state.finalize(),
+        }
+    }
+
+    /// Reset the hash state.
+    pub fn reset(&mut self) {
+        self.amount = 0;
+        match self.state {
+            State::Baseline(ref mut state) => state.reset(),
+            State::Specialized(ref mut state) => state.reset(),
+        }
+    }
+
+    /// Combine the hash state with the hash state for the subsequent block of bytes.
+    ///
+    /// Both hashers must compute the same variant.
+    pub fn combine(&mut self, other: &Self) {
+        debug_assert_eq!(self.variant, other.variant);
+        self.amount += other.amount;
+        let other_crc = other.clone().finalize();
+        match self.state {
+            State::Baseline(ref mut state) => state.combine(other_crc, other.amount),
+            State::Specialized(ref mut state) => state.combine(other_crc, other.amount),
+        }
+    }
+}
+
+impl fmt::Debug for Hasher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("crc32fast::crc64::Hasher")
+            .field("variant", &self.variant)
+            .finish()
+    }
+}
+
+impl Default for Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl hash::Hasher for Hasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes)
+    }
+
+    fn finish(&self) -> u64 {
+        self.clone().finalize()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Hasher, Variant};
+
+    quickcheck::quickcheck! {
+        fn combine(bytes_1: Vec<u8>, bytes_2: Vec<u8>, nvme: bool) -> bool {
+            let variant = if nvme { Variant::Nvme } else { Variant::Xz };
+            let mut hash_a = Hasher::new_with_variant(variant, 0, 0);
+            hash_a.update(&bytes_1);
+            hash_a.update(&bytes_2);
+            let mut hash_b = Hasher::new_with_variant(variant, 0, 0);
+            hash_b.update(&bytes_2);
+            let mut hash_c = Hasher::new_with_variant(variant, 0, 0);
+            hash_c.update(&bytes_1);
+            hash_c.combine(&hash_b);
+
+            hash_a.finalize() == hash_c.finalize()
+        }
+    }
+}
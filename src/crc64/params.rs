@@ -0,0 +1,110 @@
+/// The precomputed data for one CRC-64 polynomial.
+pub struct Params {
+    /// The polynomial, reflected.
+    pub poly: u64,
+    /// table[k][i] is the CRC of the byte i followed by k zero bytes.
+    pub table: [[u64; 256]; 8],
+    /// x^(8*2^n) modulo the reflected polynomial, for all 64 bits of a length.
+    pub x2n_table: [u64; 64],
+    /// Constants for carry-less multiplication folding, see `FoldKeys`.
+    #[allow(dead_code)]
+    pub keys: FoldKeys,
+}
+
+/// CRC-64/XZ, also known as CRC-64/GO-ECMA, using the ECMA-182 polynomial.
+pub static XZ: Params = Params::new(0xc96c5795d7870f42);
+
+/// CRC-64/NVME, as used by NVMe end-to-end data protection.
+pub static NVME: Params = Params::new(0x9a6c9329ac4bc9b5);
+
+impl Params {
+    const fn new(poly: u64) -> Self {
+        Params {
+            poly,
+            table: make_table(poly),
+            x2n_table: make_x2n_table(poly),
+            keys: FoldKeys::new(poly.reverse_bits()),
+        }
+    }
+}
+
+/// Folding constants for the reflected carry-less multiplication algorithm.
+///
+/// Multiplying two reflected 64-bit values yields a reflected 127-bit product
+/// which is one bit short of filling the 128-bit result, effectively
+/// multiplying it by an extra x. Each constant therefore is x^(n-1) modulo
+/// p(x) instead of x^n, where n is the number of bits to fold across.
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub struct FoldKeys {
+    /// Fold by 4 (512 bits), for the low and high 64 bits of a block.
+    pub k1: u64,
+    pub k2: u64,
+    /// Fold by 1 (128 bits), for the low and high 64 bits of a block.
+    pub k3: u64,
+    pub k4: u64,
+}
+
+impl FoldKeys {
+    const fn new(poly: u64) -> Self {
+        FoldKeys {
+            k1: xnmodp(4 * 128 + 64 - 1, poly).reverse_bits(),
+            k2: xnmodp(4 * 128 - 1, poly).reverse_bits(),
+            k3: xnmodp(128 + 64 - 1, poly).reverse_bits(),
+            k4: xnmodp(128 - 1, poly).reverse_bits(),
+        }
+    }
+}
+
+// Calculates x^n modulo p(x), where p(x) is the non-reflected CRC polynomial.
+const fn xnmodp(n: u32, poly: u64) -> u64 {
+    let mut p = 1u64;
+    let mut i = 0;
+    while i < n {
+        p = (p << 1) ^ ((p >> 63).wrapping_neg() & poly);
+        i += 1;
+    }
+    p
+}
+
+const fn make_table(poly: u64) -> [[u64; 256]; 8] {
+    let mut table = [[0u64; 256]; 8];
+
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut j = 0;
+        while j < 8 {
+            crc = (crc >> 1) ^ ((crc & 1).wrapping_neg() & poly);
+            j += 1;
+        }
+        table[0][i] = crc;
+        i += 1;
+    }
+
+    let mut k = 1;
+    while k < 8 {
+        let mut i = 0;
+        while i < 256 {
+            let prev = table[k - 1][i];
+            table[k][i] = (prev >> 8) ^ table[0][(prev & 0xff) as usize];
+            i += 1;
+        }
+        k += 1;
+    }
+
+    table
+}
+
+const fn make_x2n_table(poly: u64) -> [u64; 64] {
+    let mut table = [0u64; 64];
+    // x^8, reflected
+    let mut p = 1 << 55;
+    let mut n = 0;
+    while n < 64 {
+        table[n] = p;
+        p = super::combine::multiply(p, p, poly);
+        n += 1;
+    }
+    table
+}
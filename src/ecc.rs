@@ -0,0 +1,324 @@
+//! Correction of single-bit errors and short error bursts using the CRC32.
+//!
+//! The syndrome of a frame, its CRC32 xor the expected one, only depends on
+//! the bits that were flipped and not on the contents of the frame. As long as
+//! the syndromes of the errors considered are all distinct, each one identifies
+//! its error. For frames of up to 11 KiB, CRC32 has a Hamming distance of 4, so
+//! that errors of two bits are never mistaken for single-bit ones either.
+//!
+//! ```rust
+//! use crc32fast::ecc::{Correction, Corrector};
+//!
+//! let mut frame = *b"foo bar baz";
+//! let expected = crc32fast::hash(&frame);
+//! frame[5] ^= 0x10;
+//!
+//! let corrector = Corrector::new(64);
+//! let correction = corrector.correct(&mut frame, expected).unwrap();
+//! assert_eq!(correction, Correction::Data { offset: 44, pattern: 1 });
+//! assert_eq!(&frame, b"foo bar baz");
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::table::CRC32_TABLE;
+
+/// The outcome of a successful error correction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Correction {
+    /// The frame matched the expected CRC32, so nothing was changed.
+    NoError,
+    /// Bits of the frame were wrong, and have been flipped back.
+    ///
+    /// Bits are numbered in transmission order, with bit `i` being the bit
+    /// with value `1 << (i % 8)` of byte `i / 8`. `offset` is the first bit
+    /// that was flipped, and bit `k` of `pattern` is set if bit `offset + k`
+    /// was.
+    Data {
+        /// The offset of the first flipped bit in the frame.
+        offset: u64,
+        /// The flipped bits, relative to `offset`.
+        pattern: u32,
+    },
+    /// The frame was intact, but the expected CRC32 had bits flipped, namely
+    /// those set in `pattern`.
+    Checksum {
+        /// The xor of the expected and the actual CRC32 of the frame.
+        pattern: u32,
+    },
+}
+
+/// The error returned when a frame can't be corrected.
+///
+/// Either the error is not among those the [`Corrector`] was built for, or
+/// its syndrome is shared with another one, so that it can't be told apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct UncorrectableError(());
+
+impl fmt::Display for UncorrectableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("uncorrectable CRC32 error")
+    }
+}
+
+impl Error for UncorrectableError {}
+
+// An error pattern, with bits counted from the end of the frame: bit `k` of
+// `pattern` stands for the bit `end + k` positions before the last one.
+#[derive(Clone, Copy, Debug)]
+enum Entry {
+    Data { end: u64, pattern: u32 },
+    Checksum { pattern: u32 },
+}
+
+/// Corrects errors in frames using a table of syndromes.
+pub struct Corrector {
+    max_len: usize,
+    max_burst: u32,
+    // `None` for syndromes shared by several errors.
+    table: HashMap<u32, Option<Entry>>,
+}
+
+impl Corrector {
+    /// Create a new `Corrector` for single-bit errors in frames of up to
+    /// `max_len` bytes.
+    pub fn new(max_len: usize) -> Self {
+        Self::with_max_burst(max_len, 1)
+    }
+
+    /// Create a new `Corrector` for error bursts of up to `max_burst` bits in
+    /// frames of up to `max_len` bytes.
+    ///
+    /// A burst is any error whose flipped bits all lie within `max_burst`
+    /// consecutive bits. The table holds `2^(max_burst - 1)` entries per bit of
+    /// the frame, so this is only practical for short bursts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_burst` is not between 1 and 16.
+    pub fn with_max_burst(max_len: usize, max_burst: u32) -> Self {
+        assert!((1..=16).contains(&max_burst), "unsupported burst length");
+
+        let mut corrector = Corrector {
+            max_len,
+            max_burst,
+            table: HashMap::new(),
+        };
+
+        // The syndrome of flipping bit `7 - k` of the last byte, which is the
+        // CRC of that single bit without the initial and final inversions.
+        let mut bytes = [0u32; 8];
+        for (k, syndrome) in bytes.iter_mut().enumerate() {
+            *syndrome = CRC32_TABLE[0][0x80 >> k];
+        }
+        // The syndromes of single bits, counted from the end of the frame. The
+        // burst patterns need a few beyond the start of the frame as well.
+        let mut singles = Vec::with_capacity(max_len * 8 + max_burst as usize + 8);
+        while singles.len() < max_len * 8 + max_burst as usize {
+            singles.extend_from_slice(&bytes);
+            // Moving the bits one byte away from the end is like appending a
+            // zero byte.
+            for syndrome in bytes.iter_mut() {
+                *syndrome = CRC32_TABLE[0][(*syndrome & 0xff) as usize] ^ (*syndrome >> 8);
+            }
+        }
+
+        for end in 0..max_len as u64 * 8 {
+            let singles = &singles[end as usize..];
+            for pattern in burst_patterns(max_burst) {
+                let syndrome = (0..32)
+                    .filter(|k| pattern >> k & 1 != 0)
+                    .fold(0, |acc, k| acc ^ singles[k]);
+                corrector.insert(syndrome, Entry::Data { end, pattern });
+            }
+        }
+        for shift in 0..32 {
+            for pattern in burst_patterns(max_burst) {
+                if pattern.leading_zeros() >= shift {
+                    corrector.insert(
+                        pattern << shift,
+                        Entry::Checksum {
+                            pattern: pattern << shift,
+                        },
+                    );
+                }
+            }
+        }
+
+        corrector
+    }
+
+    fn insert(&mut self, syndrome: u32, entry: Entry) {
+        self.table
+            .entry(syndrome)
+            .and_modify(|e| *e = None)
+            .or_insert(Some(entry));
+    }
+
+    /// Returns the maximum length of frames (in bytes) this corrector was built
+    /// for.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Returns the maximum length of error bursts (in bits) this corrector was
+    /// built for.
+    pub fn max_burst(&self) -> u32 {
+        self.max_burst
+    }
+
+    /// Correct the errors in `frame` in place, given the CRC32 it is expected
+    /// to have.
+    ///
+    /// Frames longer than `max_len` can only be corrected if the error lies in
+    /// their last `max_len` bytes.
+    pub fn correct(
+        &self,
+        frame: &mut [u8],
+        expected: u32,
+    ) -> Result<Correction, UncorrectableError> {
+        let syndrome = crate::hash(frame) ^ expected;
+        if syndrome == 0 {
+            return Ok(Correction::NoError);
+        }
+
+        match self.table.get(&syndrome) {
+            Some(&Some(Entry::Data { end, pattern })) => {
+                let frame_bits = frame.len() as u64 * 8;
+                let len = 32 - u64::from(pattern.leading_zeros());
+                if end + len > frame_bits {
+                    return Err(UncorrectableError(()));
+                }
+
+                let offset = frame_bits - end - len;
+                for k in 0..len {
+                    if pattern >> k & 1 != 0 {
+                        let bit = frame_bits - 1 - (end + k);
+                        frame[(bit / 8) as usize] ^= 1 << (bit % 8);
+                    }
+                }
+                Ok(Correction::Data {
+                    offset,
+                    pattern: pattern.reverse_bits() >> (32 - len),
+                })
+            }
+            Some(&Some(Entry::Checksum { pattern })) => Ok(Correction::Checksum { pattern }),
+            _ => Err(UncorrectableError(())),
+        }
+    }
+}
+
+impl fmt::Debug for Corrector {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("crc32fast::ecc::Corrector")
+            .field("max_len", &self.max_len)
+            .field("max_burst", &self.max_burst)
+            .finish()
+    }
+}
+
+// All error patterns spanning at most `max_burst` bits, aligned such that
+// their lowest bit is set.
+fn burst_patterns(max_burst: u32) -> impl Iterator<Item = u32> {
+    let single = core::iter::once(1);
+    let longer = (2..=max_burst).flat_map(|len| {
+        let ends = 1 | 1 << (len - 1);
+        (0..1 << (len - 2)).map(move |middle| ends | middle << 1)
+    });
+    single.chain(longer)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Correction, Corrector, UncorrectableError};
+
+    #[test]
+    fn burst_patterns() {
+        let patterns: Vec<u32> = super::burst_patterns(4).collect();
+        assert_eq!(
+            patterns,
+            [0b1, 0b11, 0b101, 0b111, 0b1001, 0b1011, 0b1101, 0b1111]
+        );
+    }
+
+    quickcheck::quickcheck! {
+        fn single_bit(frame: Vec<u8>, bit: usize) -> bool {
+            let frame = &frame[..frame.len().min(128)];
+            if frame.is_empty() {
+                return true;
+            }
+            let bit = bit % (frame.len() * 8);
+            let expected = crate::hash(frame);
+            let mut received = frame.to_vec();
+            received[bit / 8] ^= 1 << (bit % 8);
+
+            let corrector = Corrector::new(128);
+            let correction = corrector.correct(&mut received, expected);
+            correction == Ok(Correction::Data { offset: bit as u64, pattern: 1 }) && received == frame
+        }
+
+        fn burst(frame: Vec<u8>, bit: usize, pattern: u8) -> bool {
+            let frame = &frame[..frame.len().min(32)];
+            // Bursts of up to 6 bits, with the lowest bit set.
+            let pattern = u32::from(pattern) & 0x3f | 1;
+            let len = 32 - pattern.leading_zeros() as usize;
+            if frame.len() * 8 < len {
+                return true;
+            }
+            let bit = bit % (frame.len() * 8 + 1 - len);
+            let expected = crate::hash(frame);
+            let mut received = frame.to_vec();
+            for k in 0..len {
+                if pattern >> k & 1 != 0 {
+                    received[(bit + k) / 8] ^= 1 << ((bit + k) % 8);
+                }
+            }
+
+            let corrector = Corrector::with_max_burst(32, 6);
+            let correction = corrector.correct(&mut received, expected);
+            correction == Ok(Correction::Data { offset: bit as u64, pattern }) && received == frame
+        }
+
+        fn checksum(frame: Vec<u8>, bit: u8) -> bool {
+            let pattern = 1 << (bit % 32);
+            let expected = crate::hash(&frame) ^ pattern;
+            let mut received = frame.clone();
+
+            let corrector = Corrector::new(128);
+            let correction = corrector.correct(&mut received, expected);
+            correction == Ok(Correction::Checksum { pattern }) && received == frame
+        }
+
+        fn two_bits(frame: Vec<u8>, bit_1: usize, bit_2: usize) -> bool {
+            let frame = &frame[..frame.len().min(128)];
+            let bits = frame.len() * 8;
+            if bits < 2 {
+                return true;
+            }
+            let bit_1 = bit_1 % bits;
+            let bit_2 = (bit_1 + 1 + bit_2 % (bits - 1)) % bits;
+            let expected = crate::hash(frame);
+            let mut received = frame.to_vec();
+            received[bit_1 / 8] ^= 1 << (bit_1 % 8);
+            received[bit_2 / 8] ^= 1 << (bit_2 % 8);
+
+            // The Hamming distance of CRC32 at these lengths is larger than 3,
+            // so two flipped bits can't look like one.
+            let corrector = Corrector::new(128);
+            corrector.correct(&mut received, expected) == Err(UncorrectableError(()))
+        }
+    }
+
+    #[test]
+    fn no_error() {
+        let mut frame = *b"foo bar baz";
+        let corrector = Corrector::new(16);
+        let expected = crate::hash(&frame);
+        assert_eq!(
+            corrector.correct(&mut frame, expected),
+            Ok(Correction::NoError)
+        );
+    }
+}
@@ -0,0 +1,468 @@
+//! CRC-32 computation for arbitrary parameters.
+//!
+//! Besides the IEEE variant computed by [`crate::Hasher`], many other CRC-32
+//! algorithms are in common use. They differ in their polynomial, initial
+//! value, bit order and final xor value, which are captured by
+//! [`Crc32Params`]. A [`Crc32`] engine builds the lookup tables for a set of
+//! parameters once, and can then be used to create any number of
+//! [`GenericHasher`]s:
+//!
+//! ```rust
+//! use crc32fast::generic::{Crc32, CRC_32_BZIP2};
+//!
+//! static BZIP2: Crc32 = Crc32::new(CRC_32_BZIP2);
+//!
+//! let mut hasher = BZIP2.hasher();
+//! hasher.update(b"123456789");
+//! assert_eq!(hasher.finalize(), 0xfc891918);
+//! assert_eq!(BZIP2.checksum(b"123456789"), 0xfc891918);
+//! ```
+//!
+//! The parameters of the most common algorithms are available as constants,
+//! named after their entries in the [CRC catalogue], and are also listed in
+//! [`CATALOG`].
+//!
+//! [CRC catalogue]: https://reveng.sourceforge.io/crc-catalogue/17plus.htm#crc.cat-bits.32
+
+use core::fmt;
+
+/// The parameters of a CRC-32 algorithm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Crc32Params {
+    /// The generator polynomial, in normal (MSB-first) notation, without the
+    /// implicit x^32 term.
+    pub poly: u32,
+    /// The initial register value, unreflected.
+    pub init: u32,
+    /// Whether the bits of each input byte are processed LSB-first.
+    pub refin: bool,
+    /// Whether the register is reflected before the final xor.
+    pub refout: bool,
+    /// The value xored into the register to produce the checksum.
+    pub xorout: u32,
+}
+
+/// CRC-32/ISO-HDLC, the IEEE variant computed by [`crate::Hasher`].
+pub const CRC_32_ISO_HDLC: Crc32Params = Crc32Params {
+    poly: 0x04c11db7,
+    init: 0xffffffff,
+    refin: true,
+    refout: true,
+    xorout: 0xffffffff,
+};
+
+/// CRC-32/BZIP2, also known as CRC-32/AAL5 and CRC-32/DECT-B.
+pub const CRC_32_BZIP2: Crc32Params = Crc32Params {
+    poly: 0x04c11db7,
+    init: 0xffffffff,
+    refin: false,
+    refout: false,
+    xorout: 0xffffffff,
+};
+
+/// CRC-32/MPEG-2, as used by MPEG transport streams.
+pub const CRC_32_MPEG_2: Crc32Params = Crc32Params {
+    poly: 0x04c11db7,
+    init: 0xffffffff,
+    refin: false,
+    refout: false,
+    xorout: 0x00000000,
+};
+
+/// CRC-32/CKSUM, the checksum of the POSIX `cksum` utility.
+pub const CRC_32_CKSUM: Crc32Params = Crc32Params {
+    poly: 0x04c11db7,
+    init: 0x00000000,
+    refin: false,
+    refout: false,
+    xorout: 0xffffffff,
+};
+
+/// CRC-32/JAMCRC, the IEEE variant without the final inversion.
+pub const CRC_32_JAMCRC: Crc32Params = Crc32Params {
+    poly: 0x04c11db7,
+    init: 0xffffffff,
+    refin: true,
+    refout: true,
+    xorout: 0x00000000,
+};
+
+/// CRC-32/AUTOSAR, as specified by the AUTOSAR E2E library.
+pub const CRC_32_AUTOSAR: Crc32Params = Crc32Params {
+    poly: 0xf4acfb13,
+    init: 0xffffffff,
+    refin: true,
+    refout: true,
+    xorout: 0xffffffff,
+};
+
+/// CRC-32/AIXM, also known as CRC-32Q.
+pub const CRC_32_AIXM: Crc32Params = Crc32Params {
+    poly: 0x814141ab,
+    init: 0x00000000,
+    refin: false,
+    refout: false,
+    xorout: 0x00000000,
+};
+
+/// CRC-32/BASE91-D, also known as CRC-32D.
+pub const CRC_32_BASE91_D: Crc32Params = Crc32Params {
+    poly: 0xa833982b,
+    init: 0xffffffff,
+    refin: true,
+    refout: true,
+    xorout: 0xffffffff,
+};
+
+/// CRC-32/ISCSI, the CRC-32C variant computed by [`crate::crc32c::Hasher`].
+pub const CRC_32_ISCSI: Crc32Params = Crc32Params {
+    poly: 0x1edc6f41,
+    init: 0xffffffff,
+    refin: true,
+    refout: true,
+    xorout: 0xffffffff,
+};
+
+/// CRC-32/XFER.
+pub const CRC_32_XFER: Crc32Params = Crc32Params {
+    poly: 0x000000af,
+    init: 0x00000000,
+    refin: false,
+    refout: false,
+    xorout: 0x00000000,
+};
+
+/// CRC-32/CD-ROM-EDC, as used by CD-ROM sectors.
+pub const CRC_32_CD_ROM_EDC: Crc32Params = Crc32Params {
+    poly: 0x8001801b,
+    init: 0x00000000,
+    refin: true,
+    refout: true,
+    xorout: 0x00000000,
+};
+
+/// All named presets in this module, keyed by their catalogue name.
+pub const CATALOG: &[(&str, Crc32Params)] = &[
+    ("CRC-32/ISO-HDLC", CRC_32_ISO_HDLC),
+    ("CRC-32/BZIP2", CRC_32_BZIP2),
+    ("CRC-32/MPEG-2", CRC_32_MPEG_2),
+    ("CRC-32/CKSUM", CRC_32_CKSUM),
+    ("CRC-32/JAMCRC", CRC_32_JAMCRC),
+    ("CRC-32/AUTOSAR", CRC_32_AUTOSAR),
+    ("CRC-32/AIXM", CRC_32_AIXM),
+    ("CRC-32/BASE91-D", CRC_32_BASE91_D),
+    ("CRC-32/ISCSI", CRC_32_ISCSI),
+    ("CRC-32/XFER", CRC_32_XFER),
+    ("CRC-32/CD-ROM-EDC", CRC_32_CD_ROM_EDC),
+];
+
+/// A CRC-32 engine for a given set of parameters.
+#[derive(Clone)]
+pub struct Crc32 {
+    params: Crc32Params,
+    // The initial value in register orientation.
+    init: u32,
+    table: [[u32; 256]; 16],
+    // Constants for the carry-less multiplication folding of non-reflected
+    // polynomials.
+    msb_keys: crate::specialized::MsbKeys,
+    // x^(8*2^n) modulo the reflected polynomial, for all 64 bits of a length.
+    x2n_table: [u32; 64],
+}
+
+impl Crc32 {
+    /// Create a new engine, building the lookup tables for `params`.
+    pub const fn new(params: Crc32Params) -> Self {
+        // The polynomial and initial value in register orientation.
+        let (poly, init) = if params.refin {
+            (params.poly.reverse_bits(), params.init.reverse_bits())
+        } else {
+            (params.poly, params.init)
+        };
+        Crc32 {
+            params,
+            init,
+            table: crate::table::make_table(poly, params.refin),
+            msb_keys: crate::specialized::MsbKeys::new(params.poly),
+            // x^8, reflected
+            x2n_table: crate::combine::make_x2n_table(1 << 23, params.poly.reverse_bits()),
+        }
+    }
+
+    /// Returns the parameters of this engine.
+    pub fn params(&self) -> &Crc32Params {
+        &self.params
+    }
+
+    /// Create a new `GenericHasher` using this engine.
+    pub fn hasher(&self) -> GenericHasher<'_> {
+        GenericHasher::new(self)
+    }
+
+    /// Computes the checksum of a byte slice.
+    pub fn checksum(&self, buf: &[u8]) -> u32 {
+        let mut h = self.hasher();
+        h.update(buf);
+        h.finalize()
+    }
+
+    fn update(&self, reg: u32, buf: &[u8]) -> u32 {
+        if self.params.refin {
+            !crate::baseline::update_fast_16_with(&self.table, !reg, buf)
+        } else {
+            update_fast_16_msb(&self.table, reg, buf)
+        }
+    }
+
+    fn finalize(&self, reg: u32) -> u32 {
+        let reg = if self.params.refin != self.params.refout {
+            reg.reverse_bits()
+        } else {
+            reg
+        };
+        reg ^ self.params.xorout
+    }
+
+    // Computes the register for `a || b` from the registers for `a` and `b`.
+    fn combine(&self, reg1: u32, reg2: u32, len2: u64) -> u32 {
+        if len2 == 0 {
+            return reg1;
+        }
+
+        // The register for `b` already accounts for the initial value, so
+        // strip it from `a` before shifting it past `b`.
+        let reg1 = reg1 ^ self.init;
+        if self.params.refin {
+            crate::combine::combine_with(
+                reg1,
+                reg2,
+                len2,
+                self.params.poly.reverse_bits(),
+                &self.x2n_table,
+            )
+        } else {
+            crate::combine::combine_with(
+                reg1.reverse_bits(),
+                reg2.reverse_bits(),
+                len2,
+                self.params.poly.reverse_bits(),
+                &self.x2n_table,
+            )
+            .reverse_bits()
+        }
+    }
+}
+
+impl fmt::Debug for Crc32 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("crc32fast::generic::Crc32")
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+#[derive(Clone)]
+/// Represents an in-progress CRC-32 computation with arbitrary parameters.
+pub struct GenericHasher<'a> {
+    crc: &'a Crc32,
+    amount: u64,
+    state: u32,
+    fold: Option<crate::specialized::MsbFold>,
+}
+
+impl<'a> GenericHasher<'a> {
+    /// Create a new `GenericHasher` using the given engine.
+    ///
+    /// For non-reflected parameters, this will perform a CPU feature detection
+    /// at runtime to select the most optimal implementation for the current
+    /// processor architecture.
+    pub fn new(crc: &'a Crc32) -> Self {
+        let fold = if crc.params.refin {
+            None
+        } else {
+            crate::specialized::MsbFold::new()
+        };
+        GenericHasher {
+            crc,
+            amount: 0,
+            state: crc.init,
+            fold,
+        }
+    }
+
+    /// Process the given byte slice and update the hash state.
+    pub fn update(&mut self, mut buf: &[u8]) {
+        self.amount += buf.len() as u64;
+        if let Some(ref fold) = self.fold {
+            let (state, rest) = fold.update(self.state, buf, &self.crc.msb_keys);
+            self.state = state;
+            buf = rest;
+        }
+        self.state = self.crc.update(self.state, buf);
+    }
+
+    /// Finalize the hash state and return the computed CRC-32 value.
+    pub fn finalize(self) -> u32 {
+        self.crc.finalize(self.state)
+    }
+
+    /// Reset the hash state.
+    pub fn reset(&mut self) {
+        self.amount = 0;
+        self.state = self.crc.init;
+    }
+
+    /// Combine the hash state with the hash state for the subsequent block of bytes.
+    ///
+    /// Both hashers must have been created from engines with the same parameters.
+    pub fn combine(&mut self, other: &Self) {
+        debug_assert_eq!(self.crc.params, other.crc.params);
+        self.amount += other.amount;
+        self.state = self.crc.combine(self.state, other.state, other.amount);
+    }
+}
+
+impl<'a> fmt::Debug for GenericHasher<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("crc32fast::generic::GenericHasher")
+            .field("params", &self.crc.params)
+            .finish()
+    }
+}
+
+// Slice-by-16 update for non-reflected (MSB-first) algorithms. Unlike the
+// reflected variant in `baseline`, the register is passed and returned as is.
+fn update_fast_16_msb(table: &[[u32; 256]; 16], mut crc: u32, mut buf: &[u8]) -> u32 {
+    while buf.len() >= 16 {
+        let w0 = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]) ^ crc;
+        crc = table[0x0][buf[0xf] as usize]
+            ^ table[0x1][buf[0xe] as usize]
+            ^ table[0x2][buf[0xd] as usize]
+            ^ table[0x3][buf[0xc] as usize]
+            ^ table[0x4][buf[0xb] as usize]
+            ^ table[0x5][buf[0xa] as usize]
+            ^ table[0x6][buf[0x9] as usize]
+            ^ table[0x7][buf[0x8] as usize]
+            ^ table[0x8][buf[0x7] as usize]
+            ^ table[0x9][buf[0x6] as usize]
+            ^ table[0xa][buf[0x5] as usize]
+            ^ table[0xb][buf[0x4] as usize]
+            ^ table[0xc][(w0 & 0xFF) as usize]
+            ^ table[0xd][((w0 >> 8) & 0xFF) as usize]
+            ^ table[0xe][((w0 >> 16) & 0xFF) as usize]
+            ^ table[0xf][(w0 >> 24) as usize];
+        buf = &buf[16..];
+    }
+
+    update_slow_msb(table, crc, buf)
+}
+
+fn update_slow_msb(table: &[[u32; 256]; 16], mut crc: u32, buf: &[u8]) -> u32 {
+    for &byte in buf.iter() {
+        crc = table[0][((crc >> 24) as u8 ^ byte) as usize] ^ (crc << 8);
+    }
+
+    crc
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MIXED: Crc32Params = Crc32Params {
+        poly: 0x04c11db7,
+        init: 0x12345678,
+        refin: true,
+        refout: false,
+        xorout: 0x0f0f0f0f,
+    };
+
+    static ISO_HDLC: Crc32 = Crc32::new(CRC_32_ISO_HDLC);
+    static BZIP2: Crc32 = Crc32::new(CRC_32_BZIP2);
+    static MIXED_CRC: Crc32 = Crc32::new(MIXED);
+
+    #[test]
+    fn check() {
+        let check = |params: Crc32Params, value: u32| {
+            assert_eq!(Crc32::new(params).checksum(b"123456789"), value);
+        };
+
+        check(CRC_32_ISO_HDLC, 0xcbf43926);
+        check(CRC_32_BZIP2, 0xfc891918);
+        check(CRC_32_MPEG_2, 0x0376e6e7);
+        check(CRC_32_CKSUM, 0x765e7680);
+        check(CRC_32_JAMCRC, 0x340bc6d9);
+        check(CRC_32_AUTOSAR, 0x1697d06a);
+        check(CRC_32_AIXM, 0x3010bf7f);
+        check(CRC_32_BASE91_D, 0x87315576);
+        check(CRC_32_ISCSI, 0xe3069283);
+        check(CRC_32_XFER, 0xbd0be338);
+        check(CRC_32_CD_ROM_EDC, 0x6ec2edc4);
+        check(MIXED, 0x7cde2100);
+    }
+
+    #[test]
+    fn catalog() {
+        assert_eq!(CATALOG.len(), 11);
+        for &(_, params) in CATALOG {
+            let crc = Crc32::new(params);
+            let mut hasher = crc.hasher();
+            hasher.update(b"12345");
+            hasher.update(b"6789");
+            assert_eq!(hasher.finalize(), crc.checksum(b"123456789"));
+        }
+    }
+
+    fn combines(crc: &Crc32, bytes_1: &[u8], bytes_2: &[u8]) -> bool {
+        let mut hash_a = crc.hasher();
+        hash_a.update(bytes_1);
+        hash_a.update(bytes_2);
+        let mut hash_b = crc.hasher();
+        hash_b.update(bytes_2);
+        let mut hash_c = crc.hasher();
+        hash_c.update(bytes_1);
+        hash_c.combine(&hash_b);
+
+        hash_a.finalize() == hash_c.finalize()
+    }
+
+    quickcheck::quickcheck! {
+        fn iso_hdlc_is_the_same_as_ieee(bytes: Vec<u8>) -> bool {
+            ISO_HDLC.checksum(&bytes) == crate::hash(&bytes)
+        }
+
+        fn msb_fast_16_is_the_same_as_slow(crc: u32, bytes: Vec<u8>) -> bool {
+            update_fast_16_msb(&BZIP2.table, crc, &bytes) == update_slow_msb(&BZIP2.table, crc, &bytes)
+        }
+
+        fn msb_specialized_is_the_same_as_baseline(init: u32, seed: u8, len: u16, offset: u8) -> bool {
+            let mut baseline = BZIP2.hasher();
+            baseline.fold = None;
+            baseline.state = init;
+            let mut specialized = BZIP2.hasher();
+            specialized.state = init;
+            if specialized.fold.is_none() {
+                return true;
+            }
+            crate::specialized::check_long_against_baseline(
+                seed,
+                usize::from(len) + 1000,
+                offset,
+                |buf| {
+                    baseline.update(buf);
+                    baseline.finalize()
+                },
+                |buf| {
+                    specialized.update(buf);
+                    specialized.finalize()
+                },
+            )
+        }
+
+        fn combine(bytes_1: Vec<u8>, bytes_2: Vec<u8>) -> bool {
+            combines(&ISO_HDLC, &bytes_1, &bytes_2)
+                && combines(&BZIP2, &bytes_1, &bytes_2)
+                && combines(&MIXED_CRC, &bytes_1, &bytes_2)
+        }
+    }
+}
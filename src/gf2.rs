@@ -0,0 +1,265 @@
+//! Arithmetic on polynomials over GF(2), modulo a CRC polynomial of degree 32.
+//!
+//! This is the arithmetic behind [`combine()`](crate::combine()) and friends,
+//! for use in code that manipulates CRCs in other ways.
+//!
+//! Elements are represented as `u32` values in reflected form, with the most
+//! significant bit holding the coefficient of x^0 and the least significant one
+//! that of x^31. This is the form CRC values of reflected algorithms such as
+//! CRC-32 and CRC-32C take. Use [`to_normal`] and [`from_normal`] to convert
+//! from and to the normal form, where bit `i` holds the coefficient of x^i.
+//!
+//! ```rust
+//! use crc32fast::gf2;
+//!
+//! // Appending n zero bytes to data multiplies its CRC, without the initial
+//! // and final inversions, by x^(8n).
+//! let crc = !crc32fast::hash(b"foo bar baz");
+//! let mut hasher = crc32fast::Hasher::new();
+//! hasher.update(b"foo bar baz");
+//! hasher.update(&[0; 5]);
+//! assert_eq!(!hasher.finalize(), gf2::CRC32.mul(crc, gf2::CRC32.xpow(8 * 5)));
+//! ```
+
+use core::fmt;
+
+use crate::combine::{make_x2n_table, multiply};
+
+/// The CRC-32 (IEEE) polynomial, as used by [`hash()`](crate::hash()).
+pub const CRC32: Poly32 = Poly32::new(0x04c11db7);
+/// The CRC-32C (Castagnoli) polynomial, as used by [`crate::crc32c::hash`].
+pub const CRC32C: Poly32 = Poly32::new(0x1edc6f41);
+
+/// Arithmetic modulo a polynomial P(x) of degree 32.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Poly32 {
+    // P(x) without the x^32 term, reflected.
+    rpoly: u32,
+    // x^(2^n) modulo P(x), for all 64 bits of an exponent.
+    x2n_table: [u32; 64],
+}
+
+impl Poly32 {
+    /// The polynomial 1.
+    pub const ONE: u32 = 1 << 31;
+    /// The polynomial x.
+    pub const X: u32 = 1 << 30;
+
+    /// Create a new `Poly32` for `x^32 + poly`, where `poly` is given in
+    /// normal form like `Crc32Params::poly` of the generic module.
+    pub const fn new(poly: u32) -> Self {
+        let rpoly = poly.reverse_bits();
+        Poly32 {
+            rpoly,
+            x2n_table: make_x2n_table(Self::X, rpoly),
+        }
+    }
+
+    /// Returns the polynomial without its x^32 term, in normal form.
+    pub const fn poly(&self) -> u32 {
+        self.rpoly.reverse_bits()
+    }
+
+    /// Returns the polynomial without its x^32 term, in reflected form.
+    pub const fn reflected_poly(&self) -> u32 {
+        self.rpoly
+    }
+
+    /// Calculates `a(x) * b(x) mod P(x)`.
+    pub const fn mul(&self, a: u32, b: u32) -> u32 {
+        multiply(a, b, self.rpoly)
+    }
+
+    /// Calculates `a(x) mod P(x)` for a polynomial of degree up to 63, given in
+    /// reflected form as a `u64`, with the most significant bit holding the
+    /// coefficient of x^0.
+    pub const fn rem(&self, a: u64) -> u32 {
+        // The upper half holds the coefficients of x^0 to x^31, and is already
+        // reduced. The lower half holds those of x^32 to x^63.
+        let high = (a >> 32) as u32;
+        let low = a as u32;
+        high ^ self.mul(low, self.x2n_table[5])
+    }
+
+    /// Calculates `a(x)^n mod P(x)`.
+    pub const fn pow(&self, mut a: u32, mut n: u64) -> u32 {
+        let mut p = Self::ONE;
+        while n != 0 {
+            if n & 1 != 0 {
+                p = self.mul(p, a);
+            }
+            a = self.mul(a, a);
+            n >>= 1;
+        }
+        p
+    }
+
+    /// Calculates `x^n mod P(x)`.
+    ///
+    /// Unlike `pow(Poly32::X, n)`, this takes advantage of a precomputed table.
+    pub const fn xpow(&self, n: u64) -> u32 {
+        let mut p = Self::ONE;
+        let mut i = 0;
+        while i < 64 {
+            if n >> i & 1 != 0 {
+                p = self.mul(self.x2n_table[i], p);
+            }
+            i += 1;
+        }
+        p
+    }
+
+    /// Calculates the inverse of `a(x)` modulo P(x), that is the polynomial
+    /// `b(x)` with `a(x) * b(x) mod P(x) = 1`.
+    ///
+    /// Returns `None` if there is none, which is the case if `a(x)` shares a
+    /// factor with P(x). For irreducible polynomials such as the CRC-32 one,
+    /// only 0 has no inverse, but the CRC-32C polynomial for example is
+    /// divisible by x + 1.
+    pub const fn inverse(&self, a: u32) -> Option<u32> {
+        // The extended Euclidean algorithm, in normal form, keeping track of
+        // `s` such that `s(x) * a(x) = r(x)` modulo P(x). The degree of `s`
+        // stays below 32, so neither needs reducing.
+        let mut r0 = 1 << 32 | self.poly() as u64;
+        let mut r1 = a.reverse_bits() as u64;
+        let mut s0 = 0;
+        let mut s1 = 1;
+        while r1 != 0 {
+            let mut q = 0;
+            let mut r = r0;
+            while degree(r) >= degree(r1) {
+                let shift = degree(r) - degree(r1);
+                q ^= 1 << shift;
+                r ^= r1 << shift;
+            }
+            r0 = r1;
+            r1 = r;
+            let s = s0 ^ clmul(q, s1);
+            s0 = s1;
+            s1 = s;
+        }
+
+        if r0 == 1 {
+            Some((s0 as u32).reverse_bits())
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Debug for Poly32 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("crc32fast::gf2::Poly32")
+            .field("poly", &format_args!("{:#010x}", self.poly()))
+            .finish()
+    }
+}
+
+/// Reverses the order of the lower `width` bits of `value`.
+///
+/// The bits above `width` are discarded.
+///
+/// # Panics
+///
+/// Panics if `width` is 0 or larger than 64.
+pub const fn reflect(value: u64, width: u32) -> u64 {
+    assert!(width != 0 && width <= 64);
+    value.reverse_bits() >> (64 - width)
+}
+
+/// Converts a polynomial from reflected to normal form.
+pub const fn to_normal(a: u32) -> u32 {
+    a.reverse_bits()
+}
+
+/// Converts a polynomial from normal to reflected form.
+pub const fn from_normal(a: u32) -> u32 {
+    a.reverse_bits()
+}
+
+// The degree of a non-zero polynomial in normal form, and -1 for zero.
+const fn degree(a: u64) -> i32 {
+    63 - a.leading_zeros() as i32
+}
+
+// Multiplies two polynomials in normal form, whose product has a degree below
+// 64.
+const fn clmul(a: u64, b: u64) -> u64 {
+    let mut p = 0;
+    let mut i = 0;
+    while i < 64 {
+        if a >> i & 1 != 0 {
+            p ^= b << i;
+        }
+        i += 1;
+    }
+    p
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Poly32, CRC32, CRC32C};
+
+    #[test]
+    fn golden() {
+        assert_eq!(CRC32.poly(), 0x04c11db7);
+        assert_eq!(CRC32.reflected_poly(), 0xedb88320);
+        assert_eq!(CRC32.xpow(32), 0xedb88320);
+        assert_eq!(CRC32C.xpow(32), 0x82f63b78);
+        assert_eq!(CRC32.xpow(8 * 4), CRC32.pow(Poly32::X, 32));
+        assert_eq!(CRC32.inverse(0), None);
+        assert_eq!(CRC32.inverse(Poly32::ONE), Some(Poly32::ONE));
+        // x^-1 is (P(x) - 1) / x, as x * (P(x) - 1) / x = 1 modulo P(x).
+        assert_eq!(CRC32.inverse(Poly32::X), Some(0xdb710641));
+        assert_eq!(super::reflect(0b0001_0110, 5), 0b01101);
+        assert_eq!(super::reflect(u64::MAX, 64), u64::MAX);
+    }
+
+    #[test]
+    fn reducible() {
+        // x^32 + 1 = (x + 1)^32
+        let poly = Poly32::new(1);
+        assert_eq!(poly.inverse(0xc0000000), None);
+        assert_eq!(poly.inverse(Poly32::X), Some(1));
+    }
+
+    quickcheck::quickcheck! {
+        fn inverse(a: u32, b: u32) -> bool {
+            // The CRC-32 polynomial is irreducible, while the CRC-32C one has
+            // x + 1 as a factor, which divides exactly the polynomials with an
+            // even number of terms.
+            [(CRC32, a == 0), (CRC32C, a.count_ones() % 2 == 0)]
+                .iter()
+                .all(|&(poly, no_inverse)| match poly.inverse(a) {
+                    Some(inv) => {
+                        !no_inverse
+                            && poly.mul(a, inv) == Poly32::ONE
+                            && poly.mul(poly.mul(b, a), inv) == b
+                    }
+                    None => no_inverse,
+                })
+        }
+
+        fn pow(a: u32, n: u8) -> bool {
+            let n = u64::from(n);
+            let expected = (0..n).fold(Poly32::ONE, |p, _| CRC32C.mul(p, a));
+            CRC32C.pow(a, n) == expected
+        }
+
+        fn xpow(n: u64) -> bool {
+            CRC32.xpow(n) == CRC32.pow(Poly32::X, n)
+        }
+
+        fn rem(a: u32, b: u32) -> bool {
+            // The product of reflected polynomials, shifted left by one to make
+            // up for the missing bit.
+            let product = super::clmul(u64::from(a), u64::from(b)) << 1;
+            CRC32.rem(product) == CRC32.mul(a, b)
+        }
+
+        fn normal_form(a: u32) -> bool {
+            super::from_normal(super::to_normal(a)) == a
+                && u64::from(super::to_normal(a)) == super::reflect(u64::from(a), 32)
+        }
+    }
+}
@@ -0,0 +1,197 @@
+//! Wrappers computing the CRC32 of the data passing through an [`io::Read`] or
+//! [`io::Write`].
+//!
+//! ```rust
+//! use std::io::{self, Read};
+//!
+//! let mut reader = crc32fast::io::Reader::new(&b"foo bar baz"[..]);
+//! let mut data = Vec::new();
+//! reader.read_to_end(&mut data).unwrap();
+//! assert_eq!(reader.crc(), crc32fast::hash(b"foo bar baz"));
+//! assert_eq!(reader.len(), 11);
+//!
+//! // `Hasher` implements `io::Write` itself.
+//! let mut hasher = crc32fast::Hasher::new();
+//! io::copy(&mut &data[..], &mut hasher).unwrap();
+//! assert_eq!(hasher.finalize(), crc32fast::hash(b"foo bar baz"));
+//! ```
+
+use std::io;
+
+use crate::Hasher;
+
+/// A reader computing the CRC32 of the data read from an inner reader.
+#[derive(Clone, Debug)]
+pub struct Reader<R> {
+    inner: R,
+    hasher: Hasher,
+    len: u64,
+}
+
+impl<R> Reader<R> {
+    /// Create a new `Reader` wrapping `inner`.
+    pub fn new(inner: R) -> Self {
+        Reader {
+            inner,
+            hasher: Hasher::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the CRC32 of the data read so far.
+    pub fn crc(&self) -> u32 {
+        self.hasher.clone().finalize()
+    }
+
+    /// Returns the number of bytes read so far.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no data has been read yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner reader.
+    ///
+    /// Data read through it directly is not included in the CRC32.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Unwraps this `Reader`, returning the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: io::Read> io::Read for Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.len += n as u64;
+        Ok(n)
+    }
+}
+
+/// A writer computing the CRC32 of the data written to an inner writer.
+#[derive(Clone, Debug)]
+pub struct Writer<W> {
+    inner: W,
+    hasher: Hasher,
+    len: u64,
+}
+
+impl<W> Writer<W> {
+    /// Create a new `Writer` wrapping `inner`.
+    pub fn new(inner: W) -> Self {
+        Writer {
+            inner,
+            hasher: Hasher::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the CRC32 of the data written so far.
+    pub fn crc(&self) -> u32 {
+        self.hasher.clone().finalize()
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no data has been written yet.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner writer.
+    ///
+    /// Data written through it directly is not included in the CRC32.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this `Writer`, returning the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: io::Write> io::Write for Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Only the bytes the inner writer accepted are part of the data.
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.len += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, Read, Write};
+
+    use super::{Reader, Writer};
+
+    // Reads and writes at most `len` bytes at a time.
+    struct Short<T>(T, usize);
+
+    impl<T: Read> Read for Short<T> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.1);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    impl<T: Write> Write for Short<T> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let len = buf.len().min(self.1);
+            self.0.write(&buf[..len])
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.0.flush()
+        }
+    }
+
+    quickcheck::quickcheck! {
+        fn reader(data: Vec<u8>, read_len: u8) -> bool {
+            let mut reader = Reader::new(Short(&data[..], usize::from(read_len).max(1)));
+            let mut read = Vec::new();
+            reader.read_to_end(&mut read).unwrap();
+            read == data && reader.crc() == crate::hash(&data) && reader.len() == data.len() as u64
+        }
+
+        fn writer(data: Vec<u8>, write_len: u8) -> bool {
+            let mut writer = Writer::new(Short(Vec::new(), usize::from(write_len).max(1)));
+            writer.write_all(&data).unwrap();
+            writer.crc() == crate::hash(&data)
+                && writer.len() == data.len() as u64
+                && writer.into_inner().0 == data
+        }
+
+        fn hasher(data: Vec<u8>) -> bool {
+            let mut hasher = crate::Hasher::new();
+            io::copy(&mut &data[..], &mut hasher).unwrap();
+            hasher.finalize() == crate::hash(&data)
+        }
+    }
+}
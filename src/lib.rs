@@ -24,12 +24,114 @@
 //! let checksum = hasher.finalize();
 //! ```
 //!
+//! ### Combining checksums
+//!
+//! The [`combine()`] and [`combine_many()`] functions compute the checksum of
+//! a concatenation of blocks from the checksums and lengths of the blocks, and
+//! [`prepend()`] adds data in front of an already checksummed block. Going the
+//! other way, [`strip_prefix()`] and [`strip_suffix()`] remove a block with a
+//! known checksum from either end, and [`patch()`] updates a checksum after
+//! bytes in the middle of a block have been changed. Finally, [`forge()`] and
+//! [`forge_at()`] compute bytes that make data hash to a chosen checksum.
+//!
+//! The [`gf2`] module exposes the underlying polynomial arithmetic.
+//!
+//! ### Rolling checksums
+//!
+//! A [`RollingHasher`] computes the checksum of a fixed-size window sliding
+//! over a stream, updating it in constant time for each byte.
+//!
+//! The [`chunker`] module builds content-defined chunking for deduplication on
+//! top of it.
+//!
+#![cfg_attr(
+    feature = "std",
+    doc = "### Error correction
+
+The [`ecc`] module uses the checksum to correct single-bit errors and
+short error bursts, rather than just detecting them.
+"
+)]
+//!
+#![cfg_attr(
+    feature = "std",
+    doc = "### Reverse engineering
+
+The [`reveng`] module finds the parameters of an unknown CRC-32 algorithm
+from sample messages and their checksums.
+"
+)]
+//!
+#![cfg_attr(
+    feature = "std",
+    doc = "### I/O
+
+The [`io`] module wraps readers and writers to checksum the data passing
+through them, and [`Hasher`] implements `io::Write`, so that the checksum
+of a file can be computed with `io::copy`.
+"
+)]
+//!
+#![cfg_attr(
+    feature = "std",
+    doc = "### Polynomial analysis
+
+The [`analysis`] module computes the Hamming distance of a CRC polynomial
+and its undetectable errors of up to 4 bits as a function of the length of
+the data, to compare polynomials for new protocols.
+"
+)]
+//!
+//! ### CRC-32C
+//!
+//! The [`crc32c`] module provides the same API for the CRC-32C (Castagnoli)
+//! variant used by iSCSI, SCTP, ext4 and others.
+//!
+//! ### Other CRC-32 variants
+//!
+//! The [`generic`] module computes CRC-32 checksums for arbitrary polynomials,
+//! initial values, bit orders and final xor values, and comes with presets for
+//! the commonly used variants such as BZIP2, MPEG-2 and POSIX `cksum`.
+//!
+//! ### CRC-64
+//!
+//! The [`crc64`] module provides the same API for the CRC-64/XZ and
+//! CRC-64/NVME checksums.
+//!
+//! ### CRC-16
+//!
+//! The [`crc16`] module computes the CRC-16 family of checksums, such as
+//! CRC-16/MODBUS, CRC-16/IBM-SDLC (X.25) and CRC-16/T10-DIF.
+//!
+//! ### CRC-24
+//!
+//! The [`crc24`] module computes the CRC-24/OPENPGP and CRC-24/BLE checksums.
+//!
+//! ### Small CRCs
+//!
+//! The [`small`] module computes CRCs between 3 and 24 bits wide, such as
+//! CRC-7/MMC of SD cards or CRC-8/SMBUS.
+//!
+//! ### Custom parameters
+//!
+//! The [`generic`], [`crc16`], [`crc24`] and [`small`] modules describe CRC
+//! algorithms by their parameters in the Rocksoft™ model, as used by the [CRC
+//! catalogue]. An engine builds its lookup tables from the parameters when it
+//! is created, which is relatively expensive, so it is best created once, for
+//! example in a `static`, and then shared by all hashers using the same
+//! parameters.
+//!
+//! [CRC catalogue]: https://reveng.sourceforge.io/crc-catalogue/
+//!
 //! ## Performance
 //!
 //! This crate contains multiple CRC32 implementations:
 //!
 //! - A fast baseline implementation which processes up to 16 bytes per iteration
 //! - An optimized implementation for modern `x86` using `sse` and `pclmulqdq` instructions
+//! - An optimized CRC-32C implementation for `x86_64` using the `sse4.2` `crc32` instruction
+//! - An optimized CRC-32C implementation for `aarch64` using `crc32c` instructions
+//! - Optimized CRC-64 implementations for `x86` and `aarch64` using carry-less multiplication
 //!
 //! Calling the [`Hasher::new`] constructor at runtime will perform a feature detection to select the most
 //! optimal implementation for the current CPU feature set.
@@ -39,11 +141,31 @@
 use core::fmt;
 use core::hash;
 
+#[cfg(feature = "std")]
+pub mod analysis;
 mod baseline;
+pub mod chunker;
 mod combine;
+pub mod crc16;
+pub mod crc24;
+pub mod crc32c;
+pub mod crc64;
+#[cfg(feature = "std")]
+pub mod ecc;
+pub mod generic;
+pub mod gf2;
+#[cfg(feature = "std")]
+pub mod io;
+#[cfg(feature = "std")]
+pub mod reveng;
+mod rolling;
+pub mod small;
 mod specialized;
 mod table;
 
+pub use crate::combine::CombineOp;
+pub use crate::rolling::RollingHasher;
+
 /// Computes the CRC32 hash of a byte slice.
 ///
 /// Check out [`Hasher`] for more advanced use-cases.
@@ -53,6 +175,199 @@ pub fn hash(buf: &[u8]) -> u32 {
     h.finalize()
 }
 
+/// Computes the CRC32 of the concatenation `a || b` from `crc1 = crc(a)`,
+/// `crc2 = crc(b)` and `len2 = len(b)`.
+///
+/// This takes time logarithmic in `len2`, and does not need the contents of
+/// either `a` or `b`.
+///
+/// ```rust
+/// let crc1 = crc32fast::hash(b"foo ");
+/// let crc2 = crc32fast::hash(b"bar baz");
+/// assert_eq!(crc32fast::combine(crc1, crc2, 7), crc32fast::hash(b"foo bar baz"));
+/// ```
+pub const fn combine(crc1: u32, crc2: u32, len2: u64) -> u32 {
+    combine::combine(crc1, crc2, len2)
+}
+
+/// Computes the CRC32 of `b` from `crc = crc(a || b)`, `crc1 = crc(a)` and
+/// `len2 = len(b)`.
+///
+/// This is the inverse of [`combine()`], removing a known prefix.
+///
+/// ```rust
+/// let crc = crc32fast::hash(b"foo bar baz");
+/// let crc1 = crc32fast::hash(b"foo ");
+/// assert_eq!(crc32fast::strip_prefix(crc, crc1, 7), crc32fast::hash(b"bar baz"));
+/// ```
+pub const fn strip_prefix(crc: u32, crc1: u32, len2: u64) -> u32 {
+    combine::strip_prefix(crc, crc1, len2)
+}
+
+/// Computes the CRC32 of `a` from `crc = crc(a || b)`, `crc2 = crc(b)` and
+/// `len2 = len(b)`.
+///
+/// This is the inverse of [`combine()`], removing a known suffix.
+///
+/// ```rust
+/// let crc = crc32fast::hash(b"foo bar baz");
+/// let crc2 = crc32fast::hash(b"bar baz");
+/// assert_eq!(crc32fast::strip_suffix(crc, crc2, 7), crc32fast::hash(b"foo "));
+/// ```
+pub const fn strip_suffix(crc: u32, crc2: u32, len2: u64) -> u32 {
+    combine::strip_suffix(crc, crc2, len2)
+}
+
+/// Computes the CRC32 of `prefix || data` from `crc = crc(data)` and
+/// `len = len(data)`.
+///
+/// Only `prefix` is hashed; `data` itself isn't needed.
+///
+/// ```rust
+/// let crc = crc32fast::hash(b"bar baz");
+/// assert_eq!(crc32fast::prepend(crc, 7, b"foo "), crc32fast::hash(b"foo bar baz"));
+/// ```
+pub fn prepend(crc: u32, len: u64, prefix: &[u8]) -> u32 {
+    let mut h = Hasher::new();
+    h.update(prefix);
+    h.combine_crc(crc, len);
+    h.finalize()
+}
+
+/// Computes the CRC32 of a block after some of its bytes have been changed,
+/// from its CRC32 before the change.
+///
+/// `crc` is the checksum of the original block of `total_len` bytes, and
+/// `old` and `new` are the bytes at `offset` before and after the change. The
+/// cost depends on the length of the patch, not on the size of the block.
+///
+/// # Panics
+///
+/// Panics if `old` and `new` have different lengths, or if the patch doesn't
+/// lie within the block.
+///
+/// ```rust
+/// let crc = crc32fast::hash(b"foo bar baz");
+/// let patched = crc32fast::patch(crc, 11, 4, b"bar", b"qux");
+/// assert_eq!(patched, crc32fast::hash(b"foo qux baz"));
+/// ```
+pub fn patch(crc: u32, total_len: u64, offset: u64, old: &[u8], new: &[u8]) -> u32 {
+    assert_eq!(old.len(), new.len(), "patch must not change the length");
+    let len = old.len() as u64;
+    let tail = offset
+        .checked_add(len)
+        .and_then(|end| total_len.checked_sub(end))
+        .expect("patch out of bounds");
+
+    // CRC32 is affine, so the change to the checksum is the CRC of the change
+    // to the block, without the initial and final inversions. Leading zeros
+    // don't affect that, and trailing zeros are appended through `update_zeros`.
+    let mut h = Hasher::new_with_initial(!0);
+    let mut delta = [0u8; 64];
+    for (old, new) in old.chunks(delta.len()).zip(new.chunks(delta.len())) {
+        for ((d, o), n) in delta.iter_mut().zip(old).zip(new) {
+            *d = o ^ n;
+        }
+        h.update(&delta[..old.len()]);
+    }
+    h.update_zeros(tail);
+
+    crc ^ !h.finalize()
+}
+
+/// Computes 4 bytes which, appended to data with the CRC32 `crc`, make the
+/// CRC32 of the result equal to `target`.
+///
+/// ```rust
+/// let bytes = crc32fast::forge(crc32fast::hash(b"foo bar baz"), 0xcafebabe);
+/// let mut hasher = crc32fast::Hasher::new();
+/// hasher.update(b"foo bar baz");
+/// hasher.update(&bytes);
+/// assert_eq!(hasher.finalize(), 0xcafebabe);
+/// ```
+pub const fn forge(crc: u32, target: u32) -> [u8; 4] {
+    // Without the inversions, appending 4 bytes to data turns its CRC into
+    // their xor multiplied by x^32. Dividing the target by x^32 again leaves
+    // the bytes to be solved for.
+    (!crc ^ combine::unshift(!target, 4)).to_le_bytes()
+}
+
+/// Overwrites the 4 bytes of `buf` at `offset` such that the CRC32 of `buf`
+/// becomes `target`.
+///
+/// # Panics
+///
+/// Panics if `buf` doesn't have 4 bytes at `offset`.
+///
+/// ```rust
+/// let mut buf = *b"foo ____ bar baz";
+/// crc32fast::forge_at(&mut buf, 4, 0xcafebabe);
+/// assert_eq!(crc32fast::hash(&buf), 0xcafebabe);
+/// ```
+pub fn forge_at(buf: &mut [u8], offset: usize, target: u32) {
+    let tail = buf
+        .len()
+        .checked_sub(offset)
+        .and_then(|len| len.checked_sub(4))
+        .expect("forge offset out of bounds");
+    let bytes = &mut buf[offset..offset + 4];
+    bytes.copy_from_slice(&[0; 4]);
+    let crc = hash(buf);
+
+    // CRC32 is affine, so changing the zeros to a word w changes the CRC by w
+    // times x^32, the effect of the 4 bytes themselves, times x^(8*tail), the
+    // effect of the bytes that follow them. The 4 bytes are the change needed
+    // to hit the target divided by both.
+    let word = combine::unshift(crc ^ target, tail as u64 + 4);
+    buf[offset..offset + 4].copy_from_slice(&word.to_le_bytes());
+}
+
+/// Computes the CRC32 and the length of the concatenation of many blocks, given
+/// the `(crc, len)` pair of each block in order.
+///
+/// The blocks are combined pairwise in a balanced tree rather than one after
+/// the other, so that each combination shifts by a comparatively short length.
+/// For an empty iterator, this returns `(0, 0)`, the CRC32 of no bytes.
+///
+/// ```rust
+/// let blocks: [&[u8]; 3] = [b"foo", b" bar", b" baz"];
+/// let pairs = blocks.iter().map(|b| (crc32fast::hash(b), b.len() as u64));
+/// assert_eq!(crc32fast::combine_many(pairs), (crc32fast::hash(b"foo bar baz"), 11));
+/// ```
+pub fn combine_many<I>(iter: I) -> (u32, u64)
+where
+    I: IntoIterator<Item = (u32, u64)>,
+{
+    // Works like a binary counter: `stack[i]` holds the combination of
+    // `2^heights[i]` consecutive blocks, with strictly decreasing heights.
+    // Merging two subtrees of equal height keeps the tree balanced, and since
+    // there can't be more than 2^64 blocks, 64 slots are enough.
+    let mut stack = [(0u32, 0u64); 64];
+    let mut heights = [0u32; 64];
+    let mut depth = 0;
+
+    for mut block in iter {
+        let mut height = 0;
+        while depth > 0 && heights[depth - 1] == height {
+            depth -= 1;
+            let (crc1, len1) = stack[depth];
+            block = (combine::combine(crc1, block.0, block.1), len1 + block.1);
+            height += 1;
+        }
+        stack[depth] = block;
+        heights[depth] = height;
+        depth += 1;
+    }
+
+    let mut acc = (0, 0);
+    while depth > 0 {
+        depth -= 1;
+        let (crc1, len1) = stack[depth];
+        acc = (combine::combine(crc1, acc.0, acc.1), len1 + acc.1);
+    }
+    acc
+}
+
 #[derive(Clone)]
 enum State {
     Baseline(baseline::State),
@@ -64,6 +379,9 @@ enum State {
 pub struct Hasher {
     amount: u64,
     state: State,
+    // The operator used by the last combine, kept around as blocks of the same
+    // length are often combined repeatedly.
+    combine_op: Option<CombineOp>,
 }
 
 const DEFAULT_INIT_STATE: u32 = 0;
@@ -101,6 +419,7 @@ impl Hasher {
         Hasher {
             amount,
             state: State::Baseline(baseline::State::new(init)),
+            combine_op: None,
         }
     }
 
@@ -112,6 +431,7 @@ impl Hasher {
                 return Some(Hasher {
                     amount,
                     state: State::Specialized(state),
+                    combine_op: None,
                 });
             }
         }
@@ -127,6 +447,45 @@ impl Hasher {
         }
     }
 
+    /// Update the hash state as if `n` zero bytes had been processed.
+    ///
+    /// This takes time logarithmic in `n`, rather than linear like passing a
+    /// buffer of zeros to `update` would.
+    pub fn update_zeros(&mut self, n: u64) {
+        let op = self.combine_op(n);
+        self.amount += n;
+        match self.state {
+            State::Baseline(ref mut state) => state.update_zeros(&op),
+            State::Specialized(ref mut state) => state.update_zeros(&op),
+        }
+    }
+
+    /// Update the hash state as if `buf` had been processed `count` times.
+    ///
+    /// The block is only hashed once, and its repetitions are then assembled
+    /// by repeated squaring, which costs time logarithmic in `count`.
+    pub fn update_repeated(&mut self, buf: &[u8], mut count: u64) {
+        // Holds the CRC of `buf` repeated 2^i times in the i-th iteration,
+        // together with the operator for its length, which is squared along
+        // with it so that each doubling takes a single multiplication.
+        let mut power = self.clone();
+        power.reset();
+        power.update(buf);
+        let mut crc = power.finalize();
+        let mut op = CombineOp::new(buf.len() as u64);
+
+        while count > 0 {
+            if count & 1 != 0 {
+                self.combine_crc_with(crc, &op);
+            }
+            count >>= 1;
+            if count > 0 {
+                crc = op.apply(crc, crc);
+                op = op.square();
+            }
+        }
+    }
+
     /// Finalize the hash state and return the computed CRC32 value.
     pub fn finalize(self) -> u32 {
         match self.state {
@@ -146,11 +505,40 @@ impl Hasher {
 
     /// Combine the hash state with the hash state for the subsequent block of bytes.
     pub fn combine(&mut self, other: &Self) {
-        self.amount += other.amount;
-        let other_crc = other.clone().finalize();
+        self.combine_crc(other.clone().finalize(), other.amount);
+    }
+
+    /// Combine the hash state with the CRC32 value and length (in bytes) of the
+    /// subsequent block of bytes.
+    ///
+    /// This is equivalent to calling `combine` with a `Hasher` created through
+    /// `new_with_initial_len(crc, len)`, but doesn't require one.
+    pub fn combine_crc(&mut self, crc: u32, len: u64) {
+        let op = self.combine_op(len);
+        self.combine_crc_with(crc, &op);
+    }
+
+    /// Combine the hash state with the CRC32 value of the subsequent block of
+    /// bytes, whose length is given by the precomputed `op`.
+    pub fn combine_crc_with(&mut self, crc: u32, op: &CombineOp) {
+        self.amount += op.len2();
         match self.state {
-            State::Baseline(ref mut state) => state.combine(other_crc, other.amount),
-            State::Specialized(ref mut state) => state.combine(other_crc, other.amount),
+            State::Baseline(ref mut state) => state.combine(crc, op),
+            State::Specialized(ref mut state) => state.combine(crc, op),
+        }
+    }
+
+    // Returns the operator for `len` bytes, reusing the last one if possible.
+    fn combine_op(&mut self, len: u64) -> CombineOp {
+        match self.combine_op {
+            Some(op) if op.len2() == len => op,
+            _ => {
+                let op = match self.state {
+                    State::Baseline(ref state) => state.combine_op(len),
+                    State::Specialized(ref state) => state.combine_op(len),
+                };
+                *self.combine_op.insert(op)
+            }
         }
     }
 }
@@ -177,6 +565,18 @@ impl hash::Hasher for Hasher {
     }
 }
 
+#[cfg(feature = "std")]
+impl std::io::Write for Hasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::Hasher;
@@ -210,5 +610,100 @@ mod test {
             hash_a.combine(&hash_b);
             hash_a.finalize() == ab
         }
+
+        fn combine_crc(bytes_1: Vec<u8>, bytes_2: Vec<u8>) -> bool {
+            let mut hash_a = Hasher::new();
+            hash_a.update(&bytes_1);
+            hash_a.combine_crc(crate::hash(&bytes_2), bytes_2.len() as u64);
+
+            let mut hash_ab = Hasher::new();
+            hash_ab.update(&bytes_1);
+            hash_ab.update(&bytes_2);
+
+            hash_a.finalize() == hash_ab.finalize()
+        }
+
+        fn update_zeros(init: u32, bytes_1: Vec<u8>, n: u16, bytes_2: Vec<u8>) -> bool {
+            let zeros = vec![0; usize::from(n)];
+            let mut hash_a = Hasher::new_with_initial(init);
+            hash_a.update(&bytes_1);
+            hash_a.update(&zeros);
+            hash_a.update(&bytes_2);
+            let mut hash_b = Hasher::internal_new_baseline(init, 0);
+            hash_b.update(&bytes_1);
+            hash_b.update_zeros(u64::from(n));
+            hash_b.update(&bytes_2);
+            let mut hash_c = Hasher::new_with_initial(init);
+            hash_c.update(&bytes_1);
+            hash_c.update_zeros(u64::from(n));
+            hash_c.update(&bytes_2);
+
+            hash_a.amount == hash_c.amount
+                && hash_b.finalize() == hash_a.clone().finalize()
+                && hash_c.finalize() == hash_a.finalize()
+        }
+
+        fn update_repeated(init: u32, buf: Vec<u8>, count: u8) -> bool {
+            let mut hash_a = Hasher::new_with_initial(init);
+            for _ in 0..count {
+                hash_a.update(&buf);
+            }
+            let mut hash_b = Hasher::internal_new_baseline(init, 0);
+            hash_b.update_repeated(&buf, u64::from(count));
+            let mut hash_c = Hasher::new_with_initial(init);
+            hash_c.update_repeated(&buf, u64::from(count));
+
+            hash_a.amount == hash_c.amount
+                && hash_b.finalize() == hash_a.clone().finalize()
+                && hash_c.finalize() == hash_a.finalize()
+        }
+
+        fn prepend(prefix: Vec<u8>, data: Vec<u8>) -> bool {
+            let crc = crate::prepend(crate::hash(&data), data.len() as u64, &prefix);
+            crc == crate::hash(&[prefix, data].concat())
+        }
+
+        fn strip(bytes_1: Vec<u8>, bytes_2: Vec<u8>) -> bool {
+            let crc1 = crate::hash(&bytes_1);
+            let crc2 = crate::hash(&bytes_2);
+            let len2 = bytes_2.len() as u64;
+            let crc = crate::hash(&[bytes_1, bytes_2].concat());
+            crate::strip_prefix(crc, crc1, len2) == crc2 && crate::strip_suffix(crc, crc2, len2) == crc1
+        }
+
+        fn patch(block: Vec<u8>, offset: usize, new: Vec<u8>) -> bool {
+            let offset = offset % (block.len() + 1);
+            let len = new.len().min(block.len() - offset);
+            let new = &new[..len];
+
+            let mut patched = block.clone();
+            patched[offset..offset + len].copy_from_slice(new);
+            let crc = crate::patch(
+                crate::hash(&block),
+                block.len() as u64,
+                offset as u64,
+                &block[offset..offset + len],
+                new,
+            );
+            crc == crate::hash(&patched)
+        }
+
+        fn forge(data: Vec<u8>, target: u32) -> bool {
+            let bytes = crate::forge(crate::hash(&data), target);
+            crate::hash(&[&data[..], &bytes].concat()) == target
+        }
+
+        fn forge_at(buf: Vec<u8>, offset: usize, target: u32) -> bool {
+            let mut buf = [&[0; 4][..], &buf].concat();
+            let offset = offset % (buf.len() - 3);
+            crate::forge_at(&mut buf, offset, target);
+            crate::hash(&buf) == target
+        }
+
+        fn combine_many(blocks: Vec<Vec<u8>>) -> bool {
+            let pairs = blocks.iter().map(|b| (crate::hash(b), b.len() as u64));
+            let expected = blocks.concat();
+            crate::combine_many(pairs) == (crate::hash(&expected), expected.len() as u64)
+        }
     }
 }
@@ -0,0 +1,427 @@
+//! Reverse engineering of CRC-32 parameters from sample messages.
+//!
+//! Given a few messages together with their checksums under an unknown CRC-32
+//! algorithm, [`search`] finds the parameters of the algorithm, in the style of
+//! Greg Cook's CRC RevEng.
+//!
+//! It relies on CRCs being affine: the xor of the checksums of two messages of
+//! the same length is the CRC of the xor of the messages, without any initial
+//! or final value. That leaves a multiple of the polynomial, so the polynomial
+//! can be found among the factors of the gcd of such multiples. With the
+//! polynomial known, the initial and final values can then be solved for.
+//!
+//! ```rust
+//! use crc32fast::generic::{Crc32, CRC_32_BZIP2};
+//! use crc32fast::reveng;
+//!
+//! let bzip2 = Crc32::new(CRC_32_BZIP2);
+//! let messages: [&[u8]; 4] = [b"foo bar baz", b"hello world", b"lorem ipsum", b"123456789"];
+//! let samples: Vec<_> = messages.iter().map(|m| (*m, bzip2.checksum(m))).collect();
+//!
+//! let found = reveng::search(&samples).unwrap();
+//! assert!(found.contains(&CRC_32_BZIP2));
+//! ```
+
+use std::error::Error;
+use std::fmt;
+
+use crate::generic::{Crc32, Crc32Params};
+use crate::gf2::{self, Poly32};
+
+// The largest degree of the gcd beyond 32 for which all its factors of degree
+// 32 are searched. Each additional pair of samples usually brings the gcd down
+// to the polynomial itself, so this rarely matters.
+const MAX_COFACTOR_DEGREE: usize = 16;
+
+/// The error returned when the samples don't suffice to find the parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SearchError {
+    /// At least two different messages of the same length are needed to find
+    /// the polynomial, and more may be needed to narrow it down.
+    NotEnoughSamples,
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SearchError::NotEnoughSamples => {
+                f.write_str("not enough samples to find CRC parameters")
+            }
+        }
+    }
+}
+
+impl Error for SearchError {}
+
+/// Searches for the parameters of the CRC-32 algorithm that produced the given
+/// `(message, crc)` samples.
+///
+/// Returns all parameter sets that reproduce every sample, which may be more
+/// than one if the samples are few. If the samples leave the initial value
+/// largely undetermined, as they do if all messages have the same length, only
+/// the conventional values of all zeros and all ones are tried.
+///
+/// Returns an error if the samples are insufficient to search at all, that is
+/// if they don't contain two different messages of the same length, or if the
+/// candidate polynomials couldn't be narrowed down.
+pub fn search(samples: &[(&[u8], u32)]) -> Result<Vec<Crc32Params>, SearchError> {
+    let mut found = Vec::new();
+    let mut insufficient = false;
+
+    for &(refin, refout) in &[(true, true), (false, false), (true, false), (false, true)] {
+        let gcd = match differences_gcd(samples, refin, refout) {
+            Some(gcd) => gcd,
+            None => return Err(SearchError::NotEnoughSamples),
+        };
+        let polys = match degree_32_divisors(&gcd) {
+            Some(polys) => polys,
+            None => {
+                insufficient = true;
+                continue;
+            }
+        };
+
+        for poly in polys {
+            for params in solve(samples, poly, refin, refout) {
+                if !found.contains(&params) && matches(&params, samples) {
+                    found.push(params);
+                }
+            }
+        }
+    }
+
+    if found.is_empty() && insufficient {
+        Err(SearchError::NotEnoughSamples)
+    } else {
+        Ok(found)
+    }
+}
+
+// Returns the gcd of the differences of all samples of equal length, as
+// polynomials in which the CRC polynomial divides. Returns `None` if there are
+// no such differences.
+fn differences_gcd(samples: &[(&[u8], u32)], refin: bool, refout: bool) -> Option<BigPoly> {
+    let mut gcd: Option<BigPoly> = None;
+    for (i, &(msg1, crc1)) in samples.iter().enumerate() {
+        // Differences with the first sample of the same length span all others.
+        let first = samples[..i].iter().find(|(msg, _)| msg.len() == msg1.len());
+        let (msg2, crc2) = match first {
+            Some(&sample) => sample,
+            None => continue,
+        };
+
+        let diff = BigPoly::difference(msg1, crc1, msg2, crc2, refin, refout);
+        if diff.is_zero() {
+            continue;
+        }
+        gcd = Some(match gcd {
+            Some(gcd) => gcd.gcd(diff),
+            None => diff.without_x_factors(),
+        });
+    }
+    gcd
+}
+
+// Returns the divisors of degree 32 of `gcd`, or `None` if there may be too
+// many to enumerate.
+fn degree_32_divisors(gcd: &BigPoly) -> Option<Vec<u32>> {
+    let degree = match gcd.degree() {
+        Some(degree) if degree >= 32 => degree,
+        _ => return Some(Vec::new()),
+    };
+    let k = degree - 32;
+    if k > MAX_COFACTOR_DEGREE {
+        return None;
+    }
+
+    // The gcd is small enough to fit into 64 bits now. As it isn't divisible by
+    // x, neither are its divisors, which leaves the odd cofactors of degree k.
+    let gcd = gcd.0[0];
+    let mut polys = Vec::new();
+    let cofactors = if k == 0 {
+        1..2
+    } else {
+        (1 << k | 1)..(1 << (k + 1))
+    };
+    for cofactor in cofactors.step_by(2) {
+        let (quotient, remainder) = div_u64(gcd, cofactor);
+        if remainder == 0 {
+            polys.push(quotient as u32);
+        }
+    }
+    Some(polys)
+}
+
+// Solves for the initial and final values, given the polynomial and bit order.
+fn solve(samples: &[(&[u8], u32)], poly: u32, refin: bool, refout: bool) -> Vec<Crc32Params> {
+    // In normal form, the register after processing a message M of n bytes is
+    // init * x^(8n) + M(x) * x^32 modulo the polynomial. The latter part is
+    // known, which leaves init * x^(8n) + xorout for each sample.
+    let p = Poly32::new(poly);
+    let normalize = |crc: u32| if refout { crc.reverse_bits() } else { crc };
+    let known: Vec<(u64, u32)> = samples
+        .iter()
+        .map(|&(msg, crc)| (msg.len() as u64, normalize(crc) ^ raw_crc(poly, refin, msg)))
+        .collect();
+    let shift =
+        |init: u32, len: u64| gf2::to_normal(p.mul(gf2::from_normal(init), p.xpow(8 * len)));
+
+    let (len0, known0) = known[0];
+    solve_init(&p, &known)
+        .into_iter()
+        .map(|init| Crc32Params {
+            poly,
+            init,
+            refin,
+            refout,
+            xorout: normalize(known0 ^ shift(init, len0)),
+        })
+        .collect()
+}
+
+// Solves init * (x^(8n) + x^(8n')) = known + known' for all samples, relative
+// to the first one, as a system of linear equations in the bits of init.
+fn solve_init(p: &Poly32, known: &[(u64, u32)]) -> Vec<u32> {
+    // An xor basis of the equations: `pivots[b]` has bit b as its highest bit.
+    let mut pivots: [Option<(u32, bool)>; 32] = [None; 32];
+    let (len0, known0) = known[0];
+    for &(len, known) in &known[1..] {
+        let factor = p.xpow(8 * len) ^ p.xpow(8 * len0);
+        let mut columns = [0u32; 32];
+        for (b, column) in columns.iter_mut().enumerate() {
+            *column = gf2::to_normal(p.mul(gf2::from_normal(1 << b), factor));
+        }
+        let rhs = known ^ known0;
+
+        for j in 0..32 {
+            let mut row = (0..32).fold(0u32, |row, b| row | (columns[b] >> j & 1) << b);
+            let mut bit = rhs >> j & 1 != 0;
+            while row != 0 {
+                let b = 31 - row.leading_zeros() as usize;
+                match pivots[b] {
+                    Some((pivot, pivot_bit)) => {
+                        row ^= pivot;
+                        bit ^= pivot_bit;
+                    }
+                    None => {
+                        pivots[b] = Some((row, bit));
+                        break;
+                    }
+                }
+            }
+            if row == 0 && bit {
+                // The equations are inconsistent.
+                return Vec::new();
+            }
+        }
+    }
+
+    // Fills in the bits with pivots from the bits below them, given the free
+    // ones, in `init`.
+    let complete = |mut init: u32| {
+        for (b, pivot) in pivots.iter().enumerate() {
+            if let Some((row, bit)) = *pivot {
+                let lower = row & init & ((1 << b) - 1);
+                init = init & !(1 << b) | u32::from(bit ^ (lower.count_ones() % 2 == 1)) << b;
+            }
+        }
+        init
+    };
+
+    let free: Vec<usize> = (0..32).filter(|&b| pivots[b].is_none()).collect();
+    if free.len() <= 8 {
+        (0..1u32 << free.len())
+            .map(|assignment| {
+                let init = free
+                    .iter()
+                    .enumerate()
+                    .fold(0, |init, (i, &b)| init | (assignment >> i & 1) << b);
+                complete(init)
+            })
+            .collect()
+    } else {
+        // Too many solutions, so only try the conventional ones.
+        [0, !0]
+            .iter()
+            .copied()
+            .filter(|&init| complete(init) == init)
+            .collect()
+    }
+}
+
+fn matches(params: &Crc32Params, samples: &[(&[u8], u32)]) -> bool {
+    let crc = Crc32::new(*params);
+    samples
+        .iter()
+        .all(|&(msg, expected)| crc.checksum(msg) == expected)
+}
+
+// Computes M(x) * x^32 modulo the polynomial in normal form, that is the CRC
+// without initial and final values.
+fn raw_crc(poly: u32, refin: bool, msg: &[u8]) -> u32 {
+    let mut crc = 0u32;
+    for &byte in msg {
+        let byte = if refin { byte.reverse_bits() } else { byte };
+        crc ^= u32::from(byte) << 24;
+        for _ in 0..8 {
+            crc = (crc << 1) ^ ((crc >> 31).wrapping_neg() & poly);
+        }
+    }
+    crc
+}
+
+// Divides polynomials in normal form.
+fn div_u64(mut a: u64, b: u64) -> (u64, u64) {
+    let b_degree = 63 - b.leading_zeros();
+    let mut q = 0;
+    while a != 0 && 63 - a.leading_zeros() >= b_degree {
+        let shift = 63 - a.leading_zeros() - b_degree;
+        q |= 1 << shift;
+        a ^= b << shift;
+    }
+    (q, a)
+}
+
+// A polynomial of arbitrary degree, in normal form, with the coefficient of x^i
+// in bit `i % 64` of `self.0[i / 64]`.
+#[derive(Clone, Debug)]
+struct BigPoly(Vec<u64>);
+
+impl BigPoly {
+    // Computes (M1(x) + M2(x)) * x^32 + crc1 + crc2 for messages of equal
+    // length, which is a multiple of the CRC polynomial.
+    fn difference(
+        msg1: &[u8],
+        crc1: u32,
+        msg2: &[u8],
+        crc2: u32,
+        refin: bool,
+        refout: bool,
+    ) -> Self {
+        let bits = msg1.len() * 8 + 32;
+        let mut limbs = vec![0u64; (bits + 63) / 64];
+        for (i, (&a, &b)) in msg1.iter().zip(msg2).enumerate() {
+            let byte = a ^ b;
+            let byte = if refin { byte.reverse_bits() } else { byte };
+            let degree = 32 + 8 * (msg1.len() - 1 - i);
+            limbs[degree / 64] |= u64::from(byte) << (degree % 64);
+            if degree % 64 > 56 {
+                limbs[degree / 64 + 1] |= u64::from(byte) >> (64 - degree % 64);
+            }
+        }
+        let crc = crc1 ^ crc2;
+        limbs[0] |= u64::from(if refout { crc.reverse_bits() } else { crc });
+        BigPoly(limbs)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.iter().all(|&limb| limb == 0)
+    }
+
+    fn degree(&self) -> Option<usize> {
+        let i = self.0.iter().rposition(|&limb| limb != 0)?;
+        Some(i * 64 + 63 - self.0[i].leading_zeros() as usize)
+    }
+
+    // Divides by the largest power of x that divides the polynomial. No CRC
+    // polynomial is divisible by x, so these factors don't matter.
+    fn without_x_factors(mut self) -> Self {
+        let zeros = match self.0.iter().position(|&limb| limb != 0) {
+            Some(i) => i * 64 + self.0[i].trailing_zeros() as usize,
+            None => return self,
+        };
+        let (limbs, bits) = (zeros / 64, zeros % 64);
+        self.0.drain(..limbs);
+        if bits != 0 {
+            for i in 0..self.0.len() {
+                let next = self.0.get(i + 1).copied().unwrap_or(0);
+                self.0[i] = self.0[i] >> bits | next << (64 - bits);
+            }
+        }
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+        self
+    }
+
+    // Binary gcd, keeping both polynomials free of factors of x.
+    fn gcd(self, other: Self) -> Self {
+        let mut a = self.without_x_factors();
+        let mut b = other.without_x_factors();
+        loop {
+            if a.is_zero() {
+                return b;
+            }
+            if b.is_zero() {
+                return a;
+            }
+            if a.degree() < b.degree() {
+                core::mem::swap(&mut a, &mut b);
+            }
+            // Both have a constant term, so their sum is divisible by x.
+            for (i, &limb) in b.0.iter().enumerate() {
+                a.0[i] ^= limb;
+            }
+            a = a.without_x_factors();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{search, SearchError};
+    use crate::generic::{Crc32, Crc32Params, CATALOG};
+
+    fn samples(params: Crc32Params, seed: u8) -> Vec<(Vec<u8>, u32)> {
+        let crc = Crc32::new(params);
+        let mut x = u32::from(seed) + 1;
+        let mut next = || {
+            x = x.wrapping_mul(1103515245).wrapping_add(12345);
+            (x >> 16) as u8
+        };
+        [16, 16, 16, 9, 33]
+            .iter()
+            .map(|&len| {
+                let msg: Vec<u8> = (0..len).map(|_| next()).collect();
+                let checksum = crc.checksum(&msg);
+                (msg, checksum)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn catalog() {
+        for (name, params) in CATALOG {
+            let samples = samples(*params, 0);
+            let samples: Vec<(&[u8], u32)> = samples.iter().map(|(m, c)| (&m[..], *c)).collect();
+            let found = search(&samples).unwrap();
+            assert!(found.contains(params), "{} not in {:?}", name, found);
+        }
+    }
+
+    #[test]
+    fn not_enough_samples() {
+        let samples: [(&[u8], u32); 2] = [(b"foo", 0x8c736521), (b"hello world", 0x0d4a1185)];
+        assert_eq!(search(&samples), Err(SearchError::NotEnoughSamples));
+    }
+
+    quickcheck::quickcheck! {
+        fn random_params(poly: u32, init: u32, refin: bool, refout: bool, xorout: u32, seed: u8) -> bool {
+            let params = Crc32Params {
+                poly: poly | 1,
+                init,
+                refin,
+                refout,
+                xorout,
+            };
+            let samples = samples(params, seed);
+            let samples: Vec<(&[u8], u32)> = samples.iter().map(|(m, c)| (&m[..], *c)).collect();
+            // The samples always suffice to narrow the search down to a few
+            // candidates, among them the actual parameters.
+            match search(&samples) {
+                Ok(found) => found.contains(&params),
+                Err(SearchError::NotEnoughSamples) => false,
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use core::fmt;
+
+use crate::table::CRC32_TABLE;
+use crate::CombineOp;
+
+/// Computes the CRC32 of a fixed-size window sliding over a stream of bytes,
+/// one byte at a time.
+///
+/// After filling the window with `update`, each call to `roll` moves it by one
+/// byte in constant time, and returns the CRC32 of its new contents.
+///
+/// ```rust
+/// use crc32fast::RollingHasher;
+///
+/// let data = b"foo bar baz";
+/// let mut hasher = RollingHasher::new(4);
+/// hasher.update(&data[..4]);
+/// for i in 4..data.len() {
+///     let crc = hasher.roll(data[i - 4], data[i]);
+///     assert_eq!(crc, crc32fast::hash(&data[i - 3..=i]));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct RollingHasher {
+    window_len: u64,
+    amount: u64,
+    state: u32,
+    // The difference between the CRC32 of `b || w` and `w`, for windows `w`
+    // and each outgoing byte `b`.
+    out_table: [u32; 256],
+}
+
+impl RollingHasher {
+    /// Create a new `RollingHasher` for windows of `window_len` bytes.
+    pub fn new(window_len: u64) -> Self {
+        // CRC32 is affine, so the CRC32 of `b || w` is that of `0 || w` xor
+        // the CRC of `b` followed by `window_len` zeros, without the initial
+        // and final inversions. The table entry for `b` is the CRC of `b`, and
+        // appending zeros to it is what `combine` does. That leaves the
+        // leading zero, which only contributes through the initial value.
+        let op = CombineOp::new(window_len);
+        let leading_zero = CombineOp::new(window_len + 1).append_zeros(0) ^ op.append_zeros(0);
+
+        let mut out_table = [0; 256];
+        for (out, &crc) in out_table.iter_mut().zip(CRC32_TABLE[0].iter()) {
+            *out = op.apply(crc, 0) ^ leading_zero;
+        }
+
+        RollingHasher {
+            window_len,
+            amount: 0,
+            state: 0,
+            out_table,
+        }
+    }
+
+    /// Returns the length of the window (in bytes).
+    pub fn window_len(&self) -> u64 {
+        self.window_len
+    }
+
+    /// Process the given byte slice, to fill the initial window.
+    ///
+    /// A total of `window_len` bytes must be processed before calling `roll`.
+    pub fn update(&mut self, buf: &[u8]) {
+        self.amount += buf.len() as u64;
+        self.state = crate::baseline::update_fast_16(self.state, buf);
+    }
+
+    /// Move the window by one byte, dropping `out_byte` from its front and
+    /// adding `in_byte` to its back, and return the CRC32 of its new contents.
+    pub fn roll(&mut self, out_byte: u8, in_byte: u8) -> u32 {
+        debug_assert_eq!(self.amount, self.window_len, "window not filled");
+        let crc = !self.state;
+        let crc = CRC32_TABLE[0][((crc as u8) ^ in_byte) as usize] ^ (crc >> 8);
+        self.state = !crc ^ self.out_table[out_byte as usize];
+        self.state
+    }
+
+    /// Returns the CRC32 of the current window.
+    pub fn crc(&self) -> u32 {
+        self.state
+    }
+
+    /// Reset the hash state, emptying the window.
+    pub fn reset(&mut self) {
+        self.amount = 0;
+        self.state = 0;
+    }
+}
+
+impl fmt::Debug for RollingHasher {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("crc32fast::RollingHasher")
+            .field("window_len", &self.window_len)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RollingHasher;
+
+    quickcheck::quickcheck! {
+        fn roll(data: Vec<u8>, window_len: usize) -> bool {
+            let window_len = window_len % (data.len() + 1);
+            let mut hasher = RollingHasher::new(window_len as u64);
+            hasher.update(&data[..window_len]);
+            if hasher.crc() != crate::hash(&data[..window_len]) {
+                return false;
+            }
+            (window_len..data.len()).all(|i| {
+                let crc = hasher.roll(data[i - window_len], data[i]);
+                crc == crate::hash(&data[i + 1 - window_len..=i])
+            })
+        }
+    }
+}
@@ -0,0 +1,627 @@
+//! Checksum computation for small CRCs, between 3 and 24 bits wide.
+//!
+//! Short frames in embedded protocols are usually protected by narrow CRCs,
+//! such as the CRC-7 of SD card commands, the CRC-8 packet error code of
+//! SMBus or the CRC-5 of USB tokens. A [`SmallCrc`] engine computes any such
+//! CRC from its [`SmallCrcParams`]:
+//!
+//! ```rust
+//! use crc32fast::small::{SmallCrc, CRC_8_SMBUS};
+//!
+//! static SMBUS: SmallCrc = SmallCrc::new(CRC_8_SMBUS);
+//!
+//! let mut hasher = SMBUS.hasher();
+//! hasher.update(b"123456789");
+//! assert_eq!(hasher.finalize(), 0xf4);
+//! ```
+//!
+//! The same engine also computes the checksums of the [`crc16`](crate::crc16)
+//! and [`crc24`](crate::crc24) modules.
+//!
+//! The parameters of the most common algorithms are available as constants,
+//! named after their entries in the [CRC catalogue], and are also listed in
+//! [`CATALOG`].
+//!
+//! [CRC catalogue]: https://reveng.sourceforge.io/crc-catalogue/1-15.htm
+
+use core::fmt;
+
+/// The parameters of a CRC algorithm of at most 24 bits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SmallCrcParams {
+    /// The width of the CRC in bits, between 3 and 24.
+    pub width: u8,
+    /// The generator polynomial, in normal (MSB-first) notation, without the
+    /// implicit x^width term.
+    pub poly: u32,
+    /// The initial register value, unreflected.
+    pub init: u32,
+    /// Whether the bits of each input byte are processed LSB-first.
+    pub refin: bool,
+    /// Whether the register is reflected before the final xor.
+    pub refout: bool,
+    /// The value xored into the register to produce the checksum.
+    pub xorout: u32,
+}
+
+/// CRC-3/ROHC, as used by robust header compression.
+pub const CRC_3_ROHC: SmallCrcParams = SmallCrcParams {
+    width: 3,
+    poly: 0x3,
+    init: 0x7,
+    refin: true,
+    refout: true,
+    xorout: 0x0,
+};
+
+/// CRC-4/G-704, also known as CRC-4/ITU.
+pub const CRC_4_G_704: SmallCrcParams = SmallCrcParams {
+    width: 4,
+    poly: 0x3,
+    init: 0x0,
+    refin: true,
+    refout: true,
+    xorout: 0x0,
+};
+
+/// CRC-5/USB, as used by USB token packets.
+pub const CRC_5_USB: SmallCrcParams = SmallCrcParams {
+    width: 5,
+    poly: 0x05,
+    init: 0x1f,
+    refin: true,
+    refout: true,
+    xorout: 0x1f,
+};
+
+/// CRC-5/EPC-C1G2, as used by EPC Gen 2 RFID tags.
+pub const CRC_5_EPC_C1G2: SmallCrcParams = SmallCrcParams {
+    width: 5,
+    poly: 0x09,
+    init: 0x09,
+    refin: false,
+    refout: false,
+    xorout: 0x00,
+};
+
+/// CRC-6/G-704, also known as CRC-6/ITU.
+pub const CRC_6_G_704: SmallCrcParams = SmallCrcParams {
+    width: 6,
+    poly: 0x03,
+    init: 0x00,
+    refin: true,
+    refout: true,
+    xorout: 0x00,
+};
+
+/// CRC-7/MMC, as used by SD and MMC card commands.
+pub const CRC_7_MMC: SmallCrcParams = SmallCrcParams {
+    width: 7,
+    poly: 0x09,
+    init: 0x00,
+    refin: false,
+    refout: false,
+    xorout: 0x00,
+};
+
+/// CRC-7/UMTS.
+pub const CRC_7_UMTS: SmallCrcParams = SmallCrcParams {
+    width: 7,
+    poly: 0x45,
+    init: 0x00,
+    refin: false,
+    refout: false,
+    xorout: 0x00,
+};
+
+/// CRC-8/SMBUS, the packet error code of SMBus.
+pub const CRC_8_SMBUS: SmallCrcParams = SmallCrcParams {
+    width: 8,
+    poly: 0x07,
+    init: 0x00,
+    refin: false,
+    refout: false,
+    xorout: 0x00,
+};
+
+/// CRC-8/AUTOSAR, as specified by the AUTOSAR E2E library.
+pub const CRC_8_AUTOSAR: SmallCrcParams = SmallCrcParams {
+    width: 8,
+    poly: 0x2f,
+    init: 0xff,
+    refin: false,
+    refout: false,
+    xorout: 0xff,
+};
+
+/// CRC-8/MAXIM-DOW, as used by 1-Wire devices.
+pub const CRC_8_MAXIM_DOW: SmallCrcParams = SmallCrcParams {
+    width: 8,
+    poly: 0x31,
+    init: 0x00,
+    refin: true,
+    refout: true,
+    xorout: 0x00,
+};
+
+/// CRC-8/BLUETOOTH, as used by Bluetooth header error checks.
+pub const CRC_8_BLUETOOTH: SmallCrcParams = SmallCrcParams {
+    width: 8,
+    poly: 0xa7,
+    init: 0x00,
+    refin: true,
+    refout: true,
+    xorout: 0x00,
+};
+
+/// CRC-8/ROHC, as used by robust header compression.
+pub const CRC_8_ROHC: SmallCrcParams = SmallCrcParams {
+    width: 8,
+    poly: 0x07,
+    init: 0xff,
+    refin: true,
+    refout: true,
+    xorout: 0x00,
+};
+
+/// CRC-10/ATM, also known as CRC-10/I-610.
+pub const CRC_10_ATM: SmallCrcParams = SmallCrcParams {
+    width: 10,
+    poly: 0x233,
+    init: 0x000,
+    refin: false,
+    refout: false,
+    xorout: 0x000,
+};
+
+/// CRC-11/FLEXRAY, as used by FlexRay frame headers.
+pub const CRC_11_FLEXRAY: SmallCrcParams = SmallCrcParams {
+    width: 11,
+    poly: 0x385,
+    init: 0x01a,
+    refin: false,
+    refout: false,
+    xorout: 0x000,
+};
+
+/// CRC-12/UMTS, also known as CRC-12/3GPP.
+pub const CRC_12_UMTS: SmallCrcParams = SmallCrcParams {
+    width: 12,
+    poly: 0x80f,
+    init: 0x000,
+    refin: false,
+    refout: true,
+    xorout: 0x000,
+};
+
+/// CRC-15/CAN, as used by CAN bus frames.
+pub const CRC_15_CAN: SmallCrcParams = SmallCrcParams {
+    width: 15,
+    poly: 0x4599,
+    init: 0x0000,
+    refin: false,
+    refout: false,
+    xorout: 0x0000,
+};
+
+/// All named presets in this module, keyed by their catalogue name.
+pub const CATALOG: &[(&str, SmallCrcParams)] = &[
+    ("CRC-3/ROHC", CRC_3_ROHC),
+    ("CRC-4/G-704", CRC_4_G_704),
+    ("CRC-5/USB", CRC_5_USB),
+    ("CRC-5/EPC-C1G2", CRC_5_EPC_C1G2),
+    ("CRC-6/G-704", CRC_6_G_704),
+    ("CRC-7/MMC", CRC_7_MMC),
+    ("CRC-7/UMTS", CRC_7_UMTS),
+    ("CRC-8/SMBUS", CRC_8_SMBUS),
+    ("CRC-8/AUTOSAR", CRC_8_AUTOSAR),
+    ("CRC-8/MAXIM-DOW", CRC_8_MAXIM_DOW),
+    ("CRC-8/BLUETOOTH", CRC_8_BLUETOOTH),
+    ("CRC-8/ROHC", CRC_8_ROHC),
+    ("CRC-10/ATM", CRC_10_ATM),
+    ("CRC-11/FLEXRAY", CRC_11_FLEXRAY),
+    ("CRC-12/UMTS", CRC_12_UMTS),
+    ("CRC-15/CAN", CRC_15_CAN),
+];
+
+/// A CRC engine for a given set of parameters of at most 24 bits.
+///
+/// The engine processes `N` bytes at a time with `N` lookup tables of 1 KiB
+/// each. The default of a single table keeps it small, while larger values
+/// trade memory for speed on long inputs.
+#[derive(Clone)]
+pub struct SmallCrc<const N: usize = 1> {
+    params: SmallCrcParams,
+    // Non-reflected registers are kept left-aligned in 32 bits, and reflected
+    // ones right-aligned, which turns the CRC into a 32-bit one with the
+    // polynomial scaled by x^(32-width). This lets all widths share the 32-bit
+    // table updates, folding and combination.
+    table: [[u32; 256]; N],
+    // Constants for the carry-less multiplication folding of non-reflected
+    // polynomials.
+    msb_keys: crate::specialized::MsbKeys,
+    // x^(8*2^n) modulo the scaled polynomial, reflected, for all 64 bits of a
+    // length.
+    x2n_table: [u32; 64],
+}
+
+impl<const N: usize> SmallCrc<N> {
+    /// Create a new engine, building the lookup tables for `params`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is zero, if the width is not between 3 and 24, or if the
+    /// polynomial, initial value or final xor value don't fit in the width.
+    pub const fn new(params: SmallCrcParams) -> Self {
+        assert!(N > 0, "at least one table is needed");
+        assert!(
+            params.width >= 3 && params.width <= 24,
+            "width must be between 3 and 24"
+        );
+        let shift = 32 - params.width as u32;
+        let mask = u32::MAX >> shift;
+        assert!(
+            params.poly & !mask == 0 && params.init & !mask == 0 && params.xorout & !mask == 0,
+            "parameters must fit in the width"
+        );
+
+        let poly = params.poly << shift;
+        SmallCrc {
+            params,
+            table: crate::table::make_table(
+                if params.refin {
+                    poly.reverse_bits()
+                } else {
+                    poly
+                },
+                params.refin,
+            ),
+            msb_keys: crate::specialized::MsbKeys::new(poly),
+            // x^8, reflected
+            x2n_table: crate::combine::make_x2n_table(1 << 23, poly.reverse_bits()),
+        }
+    }
+
+    /// Returns the parameters of this engine.
+    pub fn params(&self) -> &SmallCrcParams {
+        &self.params
+    }
+
+    /// Create a new `Hasher` using this engine.
+    pub fn hasher(&self) -> Hasher<'_, N> {
+        Hasher::new(self)
+    }
+
+    /// Computes the checksum of a byte slice.
+    pub fn checksum(&self, buf: &[u8]) -> u32 {
+        let mut h = self.hasher();
+        h.update(buf);
+        h.finalize()
+    }
+
+    fn update(&self, reg: u32, buf: &[u8]) -> u32 {
+        if self.params.refin {
+            update_lsb(&self.table, reg, buf)
+        } else {
+            update_msb(&self.table, reg, buf)
+        }
+    }
+
+    fn finalize(&self, reg: u32) -> u32 {
+        let params = &self.params;
+        let shift = 32 - u32::from(params.width);
+        let reg = if params.refin { reg } else { reg >> shift };
+        let reg = if params.refin != params.refout {
+            reg.reverse_bits() >> shift
+        } else {
+            reg
+        };
+        reg ^ params.xorout
+    }
+
+    // Computes the register for `a || b` from the registers for `a` and `b`,
+    // where `b` was started from the register `init2`.
+    //
+    // A left-aligned register is the CRC times x^(32-width), and multiplying
+    // it modulo the scaled polynomial gives x^(32-width) times the result
+    // modulo the original one, so the 32-bit `combine_with` applies. Right-
+    // aligned reflected registers have the very same bit pattern as the
+    // left-aligned ones do after reflecting all 32 bits.
+    fn combine(&self, reg1: u32, reg2: u32, init2: u32, len2: u64) -> u32 {
+        if len2 == 0 {
+            return reg1;
+        }
+
+        let poly = (self.params.poly << (32 - u32::from(self.params.width))).reverse_bits();
+        if self.params.refin {
+            crate::combine::combine_with(reg1 ^ init2, reg2, len2, poly, &self.x2n_table)
+        } else {
+            crate::combine::combine_with(
+                (reg1 ^ init2).reverse_bits(),
+                reg2.reverse_bits(),
+                len2,
+                poly,
+                &self.x2n_table,
+            )
+            .reverse_bits()
+        }
+    }
+}
+
+impl<const N: usize> fmt::Debug for SmallCrc<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("crc32fast::small::SmallCrc")
+            .field("params", &self.params)
+            .finish()
+    }
+}
+
+// Converts an (unreflected) value of the width of `params` to register
+// orientation.
+const fn register(params: &SmallCrcParams, value: u32) -> u32 {
+    let value = value << (32 - params.width as u32);
+    if params.refin {
+        value.reverse_bits()
+    } else {
+        value
+    }
+}
+
+#[derive(Clone)]
+/// Represents an in-progress small CRC computation.
+pub struct Hasher<'a, const N: usize = 1> {
+    crc: &'a SmallCrc<N>,
+    amount: u64,
+    init: u32,
+    state: u32,
+    fold: Option<crate::specialized::MsbFold>,
+}
+
+impl<'a, const N: usize> Hasher<'a, N> {
+    /// Create a new `Hasher` using the given engine.
+    ///
+    /// For non-reflected parameters, this will perform a CPU feature detection
+    /// at runtime to select the most optimal implementation for the current
+    /// processor architecture.
+    pub fn new(crc: &'a SmallCrc<N>) -> Self {
+        Self::new_with_init(crc, crc.params.init)
+    }
+
+    /// Create a new `Hasher` using the given engine, but with a different
+    /// initial value than the one in its parameters.
+    ///
+    /// The initial value is given unreflected, like `SmallCrcParams::init`,
+    /// and bits beyond the width are ignored.
+    pub fn new_with_init(crc: &'a SmallCrc<N>, init: u32) -> Self {
+        let init = register(&crc.params, init & (u32::MAX >> (32 - crc.params.width)));
+        let fold = if crc.params.refin {
+            None
+        } else {
+            crate::specialized::MsbFold::new()
+        };
+        Hasher {
+            crc,
+            amount: 0,
+            init,
+            state: init,
+            fold,
+        }
+    }
+
+    /// Process the given byte slice and update the hash state.
+    pub fn update(&mut self, mut buf: &[u8]) {
+        self.amount += buf.len() as u64;
+        if let Some(ref fold) = self.fold {
+            let (state, rest) = fold.update(self.state, buf, &self.crc.msb_keys);
+            self.state = state;
+            buf = rest;
+        }
+        self.state = self.crc.update(self.state, buf);
+    }
+
+    /// Finalize the hash state and return the computed CRC value.
+    pub fn finalize(self) -> u32 {
+        self.crc.finalize(self.state)
+    }
+
+    /// Reset the hash state.
+    pub fn reset(&mut self) {
+        self.amount = 0;
+        self.state = self.init;
+    }
+
+    /// Combine the hash state with the hash state for the subsequent block of bytes.
+    ///
+    /// Both hashers must have been created from engines with the same
+    /// parameters, but may have different initial values.
+    pub fn combine(&mut self, other: &Self) {
+        debug_assert_eq!(self.crc.params, other.crc.params);
+        self.amount += other.amount;
+        self.state = self
+            .crc
+            .combine(self.state, other.state, other.init, other.amount);
+    }
+}
+
+impl<'a, const N: usize> fmt::Debug for Hasher<'a, N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("crc32fast::small::Hasher")
+            .field("params", &self.crc.params)
+            .finish()
+    }
+}
+
+// Slice-by-N update for reflected (LSB-first) registers. The first (up to) four
+// bytes of each block are xored with the register, which is shifted out
+// entirely for `N` of at least 4.
+fn update_lsb<const N: usize>(table: &[[u32; 256]; N], mut crc: u32, mut buf: &[u8]) -> u32 {
+    while buf.len() >= N {
+        let mut next = if N >= 4 { 0 } else { crc >> (8 * N) };
+        for (i, &byte) in buf[..N].iter().enumerate() {
+            let byte = if i < 4 {
+                byte ^ (crc >> (8 * i)) as u8
+            } else {
+                byte
+            };
+            next ^= table[N - 1 - i][byte as usize];
+        }
+        crc = next;
+        buf = &buf[N..];
+    }
+
+    for &byte in buf.iter() {
+        crc = table[0][((crc as u8) ^ byte) as usize] ^ (crc >> 8);
+    }
+    crc
+}
+
+// Slice-by-N update for non-reflected (MSB-first) registers.
+fn update_msb<const N: usize>(table: &[[u32; 256]; N], mut crc: u32, mut buf: &[u8]) -> u32 {
+    while buf.len() >= N {
+        let mut next = if N >= 4 { 0 } else { crc << (8 * N) };
+        for (i, &byte) in buf[..N].iter().enumerate() {
+            let byte = if i < 4 {
+                byte ^ (crc >> (24 - 8 * i)) as u8
+            } else {
+                byte
+            };
+            next ^= table[N - 1 - i][byte as usize];
+        }
+        crc = next;
+        buf = &buf[N..];
+    }
+
+    for &byte in buf.iter() {
+        crc = table[0][((crc >> 24) as u8 ^ byte) as usize] ^ (crc << 8);
+    }
+    crc
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "parameters must fit in the width")]
+    fn wide_params() {
+        SmallCrc::<1>::new(SmallCrcParams {
+            xorout: 0x100,
+            ..CRC_8_SMBUS
+        });
+    }
+
+    #[test]
+    fn check() {
+        let check = |params: SmallCrcParams, value: u32| {
+            assert_eq!(SmallCrc::<1>::new(params).checksum(b"123456789"), value);
+            assert_eq!(SmallCrc::<8>::new(params).checksum(b"123456789"), value);
+        };
+
+        check(CRC_3_ROHC, 0x6);
+        check(CRC_4_G_704, 0x7);
+        check(CRC_5_USB, 0x19);
+        check(CRC_5_EPC_C1G2, 0x00);
+        check(CRC_6_G_704, 0x06);
+        check(CRC_7_MMC, 0x75);
+        check(CRC_7_UMTS, 0x61);
+        check(CRC_8_SMBUS, 0xf4);
+        check(CRC_8_AUTOSAR, 0xdf);
+        check(CRC_8_MAXIM_DOW, 0xa1);
+        check(CRC_8_BLUETOOTH, 0x26);
+        check(CRC_8_ROHC, 0xd0);
+        check(CRC_10_ATM, 0x199);
+        check(CRC_11_FLEXRAY, 0x5a3);
+        check(CRC_12_UMTS, 0xdaf);
+        check(CRC_15_CAN, 0x59e);
+    }
+
+    #[test]
+    fn catalog() {
+        assert_eq!(CATALOG.len(), 16);
+        for &(_, params) in CATALOG {
+            let crc = SmallCrc::<1>::new(params);
+            let mut hasher = crc.hasher();
+            hasher.update(b"12345");
+            hasher.update(b"6789");
+            assert_eq!(hasher.clone().finalize(), crc.checksum(b"123456789"));
+            hasher.reset();
+            hasher.update(b"123456789");
+            assert_eq!(hasher.finalize(), crc.checksum(b"123456789"));
+        }
+    }
+
+    // Quickcheck can't generate `SmallCrcParams` directly, so they're derived
+    // from a tuple of arbitrary values, fitted to a width between 3 and 24.
+    type RawParams = (u8, u32, u32, bool, bool, u32);
+
+    fn random_params((width, poly, init, refin, refout, xorout): RawParams) -> SmallCrcParams {
+        let width = 3 + width % 22;
+        let mask = u32::MAX >> (32 - width);
+        SmallCrcParams {
+            width,
+            poly: poly & mask,
+            init: init & mask,
+            refin,
+            refout,
+            xorout: xorout & mask,
+        }
+    }
+
+    fn combines<const N: usize>(
+        crc: &SmallCrc<N>,
+        init: u32,
+        bytes_1: &[u8],
+        bytes_2: &[u8],
+    ) -> bool {
+        let mut hash_a = Hasher::new_with_init(crc, init);
+        hash_a.update(bytes_1);
+        hash_a.update(bytes_2);
+        let mut hash_b = Hasher::new_with_init(crc, init);
+        hash_b.update(bytes_2);
+        let mut hash_c = Hasher::new_with_init(crc, init);
+        hash_c.update(bytes_1);
+        hash_c.combine(&hash_b);
+
+        hash_a.finalize() == hash_c.finalize()
+    }
+
+    quickcheck::quickcheck! {
+        fn slicing_is_the_same_as_bytewise(params: RawParams, bytes: Vec<u8>) -> bool {
+            let params = random_params(params);
+            let expected = SmallCrc::<1>::new(params).checksum(&bytes);
+            SmallCrc::<2>::new(params).checksum(&bytes) == expected
+                && SmallCrc::<3>::new(params).checksum(&bytes) == expected
+                && SmallCrc::<4>::new(params).checksum(&bytes) == expected
+                && SmallCrc::<8>::new(params).checksum(&bytes) == expected
+        }
+
+        fn combine(params: RawParams, init: u32, bytes_1: Vec<u8>, bytes_2: Vec<u8>) -> bool {
+            let params = random_params(params);
+            combines(&SmallCrc::<1>::new(params), init, &bytes_1, &bytes_2)
+        }
+
+        fn msb_specialized_is_the_same_as_baseline(params: RawParams, seed: u8, len: u16, offset: u8) -> bool {
+            let params = SmallCrcParams { refin: false, ..random_params(params) };
+            let crc = SmallCrc::<1>::new(params);
+            let mut baseline = crc.hasher();
+            baseline.fold = None;
+            let mut specialized = crc.hasher();
+            if specialized.fold.is_none() {
+                return true;
+            }
+            crate::specialized::check_long_against_baseline(
+                seed,
+                usize::from(len) + 1000,
+                offset,
+                |buf| {
+                    baseline.update(buf);
+                    baseline.finalize()
+                },
+                |buf| {
+                    specialized.update(buf);
+                    specialized.finalize()
+                },
+            )
+        }
+    }
+}
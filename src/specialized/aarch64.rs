@@ -3,6 +3,8 @@ use core::arch::aarch64 as arch;
 #[derive(Clone)]
 pub struct State {
     state: u32,
+    // Whether `pmull` is available as well, to speed up `combine`.
+    pmull: bool,
 }
 
 impl State {
@@ -11,7 +13,10 @@ impl State {
         if cfg!(target_feature = "crc") {
             // SAFETY: The conditions above ensure that all
             //         required instructions are supported by the CPU.
-            Some(Self { state })
+            Some(Self {
+                state,
+                pmull: cfg!(target_feature = "neon") && cfg!(target_feature = "aes"),
+            })
         } else {
             None
         }
@@ -22,7 +27,11 @@ impl State {
         if std::arch::is_aarch64_feature_detected!("crc") {
             // SAFETY: The conditions above ensure that all
             //         required instructions are supported by the CPU.
-            Some(Self { state })
+            Some(Self {
+                state,
+                pmull: std::arch::is_aarch64_feature_detected!("neon")
+                    && std::arch::is_aarch64_feature_detected!("pmull"),
+            })
         } else {
             None
         }
@@ -42,8 +51,34 @@ impl State {
         self.state = 0;
     }
 
-    pub fn combine(&mut self, other: u32, amount: u64) {
-        self.state = crate::combine::combine(self.state, other, amount);
+    pub fn update_zeros(&mut self, op: &crate::CombineOp) {
+        self.state = if self.pmull {
+            // SAFETY: The `State::new` constructor ensures that all
+            //         required instructions are supported by the CPU.
+            op.append_zeros_with(self.state, |a, b| unsafe { multiply(a, b) })
+        } else {
+            op.append_zeros(self.state)
+        };
+    }
+
+    pub fn combine(&mut self, other: u32, op: &crate::CombineOp) {
+        self.state = if self.pmull {
+            // SAFETY: The `State::new` constructor ensures that all
+            //         required instructions are supported by the CPU.
+            op.apply_with(self.state, other, |a, b| unsafe { multiply(a, b) })
+        } else {
+            op.apply(self.state, other)
+        };
+    }
+
+    pub fn combine_op(&self, len: u64) -> crate::CombineOp {
+        if self.pmull {
+            // SAFETY: The `State::new` constructor ensures that all
+            //         required instructions are supported by the CPU.
+            crate::CombineOp::new_with(len, |a, b| unsafe { multiply(a, b) })
+        } else {
+            crate::CombineOp::new(len)
+        }
     }
 }
 
@@ -77,6 +112,19 @@ pub unsafe fn calculate(crc: u32, data: &[u8]) -> u32 {
     !c32
 }
 
+// Calculates a(x) multiplied by b(x) modulo p(x), reflected, like
+// `combine::multiply` does bit by bit.
+#[target_feature(enable = "crc", enable = "neon", enable = "aes")]
+unsafe fn multiply(a: u32, b: u32) -> u32 {
+    // The product of two reflected 32-bit polynomials is a reflected 63-bit
+    // one. Shifting it left by one bit makes it a reflected 64-bit polynomial
+    // whose upper half holds the coefficients of x^0 to x^31, and whose lower
+    // half those of x^32 to x^63. Reducing x^32 times the lower half is just
+    // what the crc32 instruction does, so no Barrett reduction is needed.
+    let x = (arch::vmull_p64(u64::from(a), u64::from(b)) as u64) << 1;
+    (x >> 32) as u32 ^ arch::__crc32w(0, x as u32)
+}
+
 #[cfg(test)]
 mod test {
     quickcheck::quickcheck! {
@@ -96,5 +144,37 @@ mod test {
             }
             aarch64.finalize() == baseline.finalize()
         }
+
+        fn multiply_against_baseline(a: u32, b: u32) -> bool {
+            let state = super::State::new(0).expect("not supported");
+            if !state.pmull {
+                return true;
+            }
+            let expected = crate::combine::multiply(a, b, 0xedb88320);
+            // SAFETY: `State::new` only sets `pmull` if all required
+            //         instructions are supported by the CPU.
+            unsafe { super::multiply(a, b) == expected }
+        }
+    }
+
+    #[test]
+    fn combine_golden() {
+        let state = super::State::new(0).expect("not supported");
+        let combine = |crc1: u32, crc2: u32, len2: u64| {
+            let mut state = super::State::new(crc1).unwrap();
+            state.combine(crc2, &state.combine_op(len2));
+            state.finalize()
+        };
+        assert_eq!(state.combine_op(0xa59d), crate::CombineOp::new(0xa59d));
+        assert_eq!(combine(0x0, 0x1, 0x0), 0x0);
+        assert_eq!(combine(0xc401f8c9, 0x00000000, 0x0), 0xc401f8c9);
+        assert_eq!(combine(0x7cba3d5e, 0xe7466d39, 0xb), 0x76365c4f);
+        assert_eq!(combine(0x576c62d6, 0x123256e1, 0x47), 0x579a636);
+        assert_eq!(combine(0x4f626f9a, 0x9e5ccbf5, 0xa59d), 0x98d43168);
+        assert_eq!(combine(0xa09b8a88, 0x815b0f48, 0x40f39511), 0xd7a5f79);
+        assert_eq!(
+            combine(0x7f6a4306, 0xbc929646, 0x828cde72b3e25301),
+            0xef922dda
+        );
     }
 }
@@ -0,0 +1,160 @@
+//! Specialized CRC-64 code for the aarch64 CPU architecture, using the same
+//! carry-less multiplication folding as the x86 implementation in
+//! `crc64_pclmulqdq.rs`, with the `pmull` instruction.
+//!
+//! Rather than reducing the final 128 bits with a Barrett reduction, they are
+//! fed through the table-driven baseline implementation, which is cheap for a
+//! single block and sidesteps the 65-bit constants the reduction would need.
+
+use core::arch::aarch64 as arch;
+
+use crate::crc64::baseline::update_fast_8;
+use crate::crc64::params::Params;
+
+#[derive(Clone)]
+pub struct State {
+    state: u64,
+    params: &'static Params,
+}
+
+impl State {
+    #[cfg(not(feature = "std"))]
+    pub fn new(state: u64, params: &'static Params) -> Option<Self> {
+        if cfg!(target_feature = "neon") && cfg!(target_feature = "aes") {
+            // SAFETY: The conditions above ensure that all
+            //         required instructions are supported by the CPU.
+            Some(Self { state, params })
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn new(state: u64, params: &'static Params) -> Option<Self> {
+        if std::arch::is_aarch64_feature_detected!("neon")
+            && std::arch::is_aarch64_feature_detected!("pmull")
+        {
+            // SAFETY: The conditions above ensure that all
+            //         required instructions are supported by the CPU.
+            Some(Self { state, params })
+        } else {
+            None
+        }
+    }
+
+    pub fn update(&mut self, buf: &[u8]) {
+        // SAFETY: The `State::new` constructor ensures that all
+        //         required instructions are supported by the CPU.
+        self.state = unsafe { calculate(self.params, self.state, buf) }
+    }
+
+    pub fn finalize(self) -> u64 {
+        self.state
+    }
+
+    pub fn reset(&mut self) {
+        self.state = 0;
+    }
+
+    pub fn combine(&mut self, other: u64, amount: u64) {
+        self.state = crate::crc64::combine::combine(self.params, self.state, other, amount);
+    }
+}
+
+// target_feature is necessary to allow rustc to inline the vmull_p64 wrapper
+#[target_feature(enable = "neon", enable = "aes")]
+unsafe fn calculate(params: &Params, crc: u64, mut data: &[u8]) -> u64 {
+    if data.len() < 128 {
+        return update_fast_8(params, crc, data);
+    }
+
+    // Step 1: fold by 4 loop
+    let mut x3 = get(&mut data);
+    let mut x2 = get(&mut data);
+    let mut x1 = get(&mut data);
+    let mut x0 = get(&mut data);
+
+    // fold in our initial value, part of the incremental crc checksum
+    x3 ^= u128::from(!crc);
+
+    let keys = &params.keys;
+    while data.len() >= 64 {
+        x3 = reduce128(x3, get(&mut data), keys.k1, keys.k2);
+        x2 = reduce128(x2, get(&mut data), keys.k1, keys.k2);
+        x1 = reduce128(x1, get(&mut data), keys.k1, keys.k2);
+        x0 = reduce128(x0, get(&mut data), keys.k1, keys.k2);
+    }
+
+    let mut x = reduce128(x3, x2, keys.k3, keys.k4);
+    x = reduce128(x, x1, keys.k3, keys.k4);
+    x = reduce128(x, x0, keys.k3, keys.k4);
+
+    // Step 2: fold by 1 loop
+    while data.len() >= 16 {
+        x = reduce128(x, get(&mut data), keys.k3, keys.k4);
+    }
+
+    // Step 3: the remaining 128 bits are congruent to the input processed so
+    // far, so their CRC (starting from a zero register) is the CRC we're after.
+    let c = update_fast_8(params, !0, &x.to_le_bytes());
+
+    update_fast_8(params, c, data)
+}
+
+#[inline]
+#[target_feature(enable = "neon", enable = "aes")]
+unsafe fn reduce128(a: u128, b: u128, k_lo: u64, k_hi: u64) -> u128 {
+    let t1 = arch::vmull_p64(a as u64, k_lo);
+    let t2 = arch::vmull_p64((a >> 64) as u64, k_hi);
+    b ^ t1 ^ t2
+}
+
+fn get(a: &mut &[u8]) -> u128 {
+    let r = u128::from_le_bytes(a[..16].try_into().unwrap());
+    *a = &a[16..];
+    r
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crc64::params::{NVME, XZ};
+
+    quickcheck::quickcheck! {
+        fn check_against_baseline(init: u64, chunks: Vec<(Vec<u8>, usize)>, nvme: bool) -> bool {
+            let params = if nvme { &NVME } else { &XZ };
+            let mut baseline = crate::crc64::baseline::State::new(init, params);
+            let mut aarch64 = super::State::new(init, params).expect("not supported");
+            for (chunk, mut offset) in chunks {
+                // simulate random alignments by offsetting the slice by up to 15 bytes
+                offset &= 0xF;
+                if chunk.len() <= offset {
+                    baseline.update(&chunk);
+                    aarch64.update(&chunk);
+                } else {
+                    baseline.update(&chunk[offset..]);
+                    aarch64.update(&chunk[offset..]);
+                }
+            }
+            aarch64.finalize() == baseline.finalize()
+        }
+
+        fn check_long_against_baseline(init: u64, seed: u8, len: u16, offset: u8, nvme: bool) -> bool {
+            let params = if nvme { &NVME } else { &XZ };
+            super::super::check_long_against_baseline(
+                seed,
+                usize::from(len) + 1000,
+                offset,
+                |buf| {
+                    let mut baseline = crate::crc64::baseline::State::new(init, params);
+                    baseline.update(buf);
+                    baseline.finalize()
+                },
+                |buf| {
+                    let mut aarch64 = super::State::new(init, params).expect("not supported");
+                    aarch64.update(buf);
+                    aarch64.finalize()
+                },
+            )
+        }
+    }
+}
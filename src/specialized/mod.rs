@@ -4,11 +4,14 @@ cfg_if::cfg_if! {
         any(target_arch = "x86", target_arch = "x86_64")
     ))] {
         mod pclmulqdq;
-        pub use self::pclmulqdq::State;
+        pub use self::pclmulqdq::{MsbFold, State};
     } else if #[cfg(all(stable_arm_crc32_intrinsics, target_arch = "aarch64"))] {
         mod aarch64;
         pub use self::aarch64::State;
+        pub use self::fallback::MsbFold;
     } else {
+        pub use self::fallback::MsbFold;
+
         #[derive(Clone)]
         pub enum State {}
         impl State {
@@ -28,9 +31,184 @@ cfg_if::cfg_if! {
                 match *self {}
             }
 
+            pub fn update_zeros(&mut self, _op: &crate::CombineOp) {
+                match *self {}
+            }
+
+            pub fn combine(&mut self, _other: u32, _op: &crate::CombineOp) {
+                match *self {}
+            }
+
+            pub fn combine_op(&self, _len: u64) -> crate::CombineOp {
+                match *self {}
+            }
+        }
+    }
+}
+
+cfg_if::cfg_if! {
+    if #[cfg(target_arch = "x86_64")] {
+        mod sse42;
+        pub use self::sse42::State as Crc32cState;
+    } else if #[cfg(all(stable_arm_crc32_intrinsics, target_arch = "aarch64"))] {
+        mod aarch64_crc32c;
+        pub use self::aarch64_crc32c::State as Crc32cState;
+    } else {
+        #[derive(Clone)]
+        pub enum Crc32cState {}
+        impl Crc32cState {
+            pub fn new(_: u32) -> Option<Self> {
+                None
+            }
+
+            pub fn update(&mut self, _buf: &[u8]) {
+                match *self {}
+            }
+
+            pub fn finalize(self) -> u32 {
+                match self{}
+            }
+
+            pub fn reset(&mut self) {
+                match *self {}
+            }
+
             pub fn combine(&mut self, _other: u32, _amount: u64) {
                 match *self {}
             }
         }
     }
 }
+
+cfg_if::cfg_if! {
+    if #[cfg(all(
+        target_feature = "sse2",
+        any(target_arch = "x86", target_arch = "x86_64")
+    ))] {
+        mod crc64_pclmulqdq;
+        pub use self::crc64_pclmulqdq::State as Crc64State;
+    } else if #[cfg(all(stable_arm_crc32_intrinsics, target_arch = "aarch64"))] {
+        mod crc64_aarch64;
+        pub use self::crc64_aarch64::State as Crc64State;
+    } else {
+        #[derive(Clone)]
+        pub enum Crc64State {}
+        impl Crc64State {
+            pub fn new(_: u64, _: &'static crate::crc64::params::Params) -> Option<Self> {
+                None
+            }
+
+            pub fn update(&mut self, _buf: &[u8]) {
+                match *self {}
+            }
+
+            pub fn finalize(self) -> u64 {
+                match self{}
+            }
+
+            pub fn reset(&mut self) {
+                match *self {}
+            }
+
+            pub fn combine(&mut self, _other: u64, _amount: u64) {
+                match *self {}
+            }
+        }
+    }
+}
+
+#[allow(dead_code)]
+mod fallback {
+    #[derive(Clone, Copy)]
+    pub enum MsbFold {}
+    impl MsbFold {
+        pub fn new() -> Option<Self> {
+            None
+        }
+
+        pub fn update<'a>(
+            &self,
+            _crc: u32,
+            _buf: &'a [u8],
+            _keys: &super::MsbKeys,
+        ) -> (u32, &'a [u8]) {
+            match *self {}
+        }
+    }
+}
+
+/// Folding constants for a non-reflected 32-bit polynomial, as used by
+/// `MsbFold`. All of them are plain (MSB-first) polynomials.
+#[derive(Clone, Copy)]
+#[allow(dead_code)]
+pub struct MsbKeys {
+    k1: u32,
+    k2: u32,
+    k3: u32,
+    k4: u32,
+    k5: u32,
+    k6: u32,
+    mu: u64,
+    p: u64,
+}
+
+impl MsbKeys {
+    pub const fn new(poly: u32) -> Self {
+        MsbKeys {
+            k1: xnmodp(4 * 128 + 64, poly),
+            k2: xnmodp(4 * 128, poly),
+            k3: xnmodp(128 + 64, poly),
+            k4: xnmodp(128, poly),
+            k5: xnmodp(96, poly),
+            k6: xnmodp(64, poly),
+            mu: barrett_mu(poly),
+            p: (1 << 32) | poly as u64,
+        }
+    }
+}
+
+// Calculates x^n modulo p(x), where p(x) is the non-reflected CRC polynomial.
+const fn xnmodp(n: u32, poly: u32) -> u32 {
+    let mut p = 1u32;
+    let mut i = 0;
+    while i < n {
+        p = (p << 1) ^ ((p >> 31).wrapping_neg() & poly);
+        i += 1;
+    }
+    p
+}
+
+// Calculates ⌊x^64 / p(x)⌋, where p(x) is the non-reflected CRC polynomial.
+const fn barrett_mu(poly: u32) -> u64 {
+    let p = (1u128 << 32) | poly as u128;
+    let mut rem = 1u128 << 64;
+    let mut mu = 0u64;
+    let mut i = 64;
+    while i >= 32 {
+        if rem >> i & 1 != 0 {
+            mu |= 1 << (i - 32);
+            rem ^= p << (i - 32);
+        }
+        i -= 1;
+    }
+    mu
+}
+
+// Checks that a specialized implementation agrees with the baseline on a buffer
+// of at least `len` bytes, as quickcheck inputs are too short to reach the
+// folding loops. The buffer is derived from `seed`, and misaligned by up to 15
+// bytes according to `offset`.
+#[cfg(test)]
+pub fn check_long_against_baseline<T: PartialEq>(
+    seed: u8,
+    len: usize,
+    offset: u8,
+    baseline: impl FnOnce(&[u8]) -> T,
+    specialized: impl FnOnce(&[u8]) -> T,
+) -> bool {
+    let buf: Vec<u8> = (0..len + 0xF)
+        .map(|i| (i as u8).wrapping_mul(31).wrapping_add(seed) ^ (i >> 8) as u8)
+        .collect();
+    let buf = &buf[usize::from(offset & 0xF)..];
+    baseline(buf) == specialized(buf)
+}
@@ -60,8 +60,22 @@ impl State {
         self.state = 0;
     }
 
-    pub fn combine(&mut self, other: u32, amount: u64) {
-        self.state = crate::combine::combine(self.state, other, amount);
+    pub fn update_zeros(&mut self, op: &crate::CombineOp) {
+        // SAFETY: The `State::new` constructor ensures that all
+        //         required instructions are supported by the CPU.
+        self.state = op.append_zeros_with(self.state, |a, b| unsafe { multiply(a, b) });
+    }
+
+    pub fn combine(&mut self, other: u32, op: &crate::CombineOp) {
+        // SAFETY: The `State::new` constructor ensures that all
+        //         required instructions are supported by the CPU.
+        self.state = op.apply_with(self.state, other, |a, b| unsafe { multiply(a, b) });
+    }
+
+    pub fn combine_op(&self, len: u64) -> crate::CombineOp {
+        // SAFETY: The `State::new` constructor ensures that all
+        //         required instructions are supported by the CPU.
+        crate::CombineOp::new_with(len, |a, b| unsafe { multiply(a, b) })
     }
 }
 
@@ -171,19 +185,181 @@ unsafe fn calculate(crc: u32, mut data: &[u8]) -> u32 {
     }
 }
 
-unsafe fn reduce128(a: arch::__m128i, b: arch::__m128i, keys: arch::__m128i) -> arch::__m128i {
+// Calculates a(x) multiplied by b(x) modulo p(x), reflected, like
+// `combine::multiply` does bit by bit.
+#[target_feature(enable = "pclmulqdq", enable = "sse2", enable = "sse4.1")]
+unsafe fn multiply(a: u32, b: u32) -> u32 {
+    // The product of two reflected 32-bit polynomials is a reflected 63-bit
+    // one. Shifting it left by one bit makes it a reflected 64-bit polynomial
+    // R(x), which is just the input of the final Barrett reduction above.
+    let x = arch::_mm_clmulepi64_si128(
+        arch::_mm_cvtsi32_si128(a as i32),
+        arch::_mm_cvtsi32_si128(b as i32),
+        0x00,
+    );
+    let x = arch::_mm_slli_epi64(x, 1);
+
+    let pu = arch::_mm_set_epi64x(U_PRIME, P_X);
+    let t1 = arch::_mm_clmulepi64_si128(
+        arch::_mm_and_si128(x, arch::_mm_set_epi32(0, 0, 0, !0)),
+        pu,
+        0x10,
+    );
+    let t2 = arch::_mm_clmulepi64_si128(
+        arch::_mm_and_si128(t1, arch::_mm_set_epi32(0, 0, 0, !0)),
+        pu,
+        0x00,
+    );
+    arch::_mm_extract_epi32(arch::_mm_xor_si128(x, t2), 1) as u32
+}
+
+#[derive(Clone, Copy)]
+pub struct MsbFold(());
+
+impl MsbFold {
+    #[cfg(not(feature = "std"))]
+    pub fn new() -> Option<Self> {
+        if cfg!(target_feature = "pclmulqdq")
+            && cfg!(target_feature = "sse2")
+            && cfg!(target_feature = "ssse3")
+            && cfg!(target_feature = "sse4.1")
+        {
+            // SAFETY: The conditions above ensure that all
+            //         required instructions are supported by the CPU.
+            Some(Self(()))
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn new() -> Option<Self> {
+        if is_x86_feature_detected!("pclmulqdq")
+            && is_x86_feature_detected!("sse2")
+            && is_x86_feature_detected!("ssse3")
+            && is_x86_feature_detected!("sse4.1")
+        {
+            // SAFETY: The conditions above ensure that all
+            //         required instructions are supported by the CPU.
+            Some(Self(()))
+        } else {
+            None
+        }
+    }
+
+    pub fn update<'a>(&self, crc: u32, buf: &'a [u8], keys: &super::MsbKeys) -> (u32, &'a [u8]) {
+        // SAFETY: The `MsbFold::new` constructor ensures that all
+        //         required instructions are supported by the CPU.
+        unsafe { calculate_msb(crc, buf, keys) }
+    }
+}
+
+/// Non-reflected ("MSB-first") variant of `calculate`, for polynomials other
+/// than the IEEE one.
+///
+/// Input and output are raw CRC registers, without any inversion. Only whole
+/// 16-byte blocks are consumed, and the unprocessed tail of the input is
+/// returned alongside the register so that it can be handled by the caller's
+/// table-driven implementation.
+#[target_feature(
+    enable = "pclmulqdq",
+    enable = "sse2",
+    enable = "ssse3",
+    enable = "sse4.1"
+)]
+unsafe fn calculate_msb<'a>(
+    crc: u32,
+    mut data: &'a [u8],
+    keys: &super::MsbKeys,
+) -> (u32, &'a [u8]) {
+    if data.len() < 128 {
+        return (crc, data);
+    }
+
+    // Loading the input byte-swapped makes the first bit of each block the
+    // most significant bit of the 128-bit lane, which is what the non-reflected
+    // algorithm expects. Compared to the reflected variant this means that
+    // none of the constants need an extra factor of x, and that the result of
+    // the reduction ends up in the lower 32 bits rather than the upper ones.
+    let mask = arch::_mm_set_epi8(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15);
+
+    // Step 1: fold by 4 loop
+    let mut x3 = get_swapped(&mut data, mask);
+    let mut x2 = get_swapped(&mut data, mask);
+    let mut x1 = get_swapped(&mut data, mask);
+    let mut x0 = get_swapped(&mut data, mask);
+
+    // fold in our initial value, which lines up with the first 32 bits of input
+    x3 = arch::_mm_xor_si128(x3, arch::_mm_set_epi32(crc as i32, 0, 0, 0));
+
+    let k1k2 = arch::_mm_set_epi64x(keys.k1 as i64, keys.k2 as i64);
+    while data.len() >= 64 {
+        x3 = reduce128(x3, get_swapped(&mut data, mask), k1k2);
+        x2 = reduce128(x2, get_swapped(&mut data, mask), k1k2);
+        x1 = reduce128(x1, get_swapped(&mut data, mask), k1k2);
+        x0 = reduce128(x0, get_swapped(&mut data, mask), k1k2);
+    }
+
+    let k3k4 = arch::_mm_set_epi64x(keys.k3 as i64, keys.k4 as i64);
+    let mut x = reduce128(x3, x2, k3k4);
+    x = reduce128(x, x1, k3k4);
+    x = reduce128(x, x0, k3k4);
+
+    // Step 2: fold by 1 loop
+    while data.len() >= 16 {
+        x = reduce128(x, get_swapped(&mut data, mask), k3k4);
+    }
+
+    // Step 3: the CRC is now x(x) • x^32 mod P(x). Reduce the 128 bits to
+    // 96 bits, and then to 64 bits:
+    //
+    // x = (x[64:127] • K5) ^ (x[0:63] << 32)    // 96 bit result
+    // x = (x[64:95] • K6) ^ x[0:63]             // 64 bit result
+    let k5k6 = arch::_mm_set_epi64x(keys.k6 as i64, keys.k5 as i64);
+    let x = arch::_mm_xor_si128(
+        arch::_mm_clmulepi64_si128(x, k5k6, 0x01),
+        arch::_mm_slli_si128(arch::_mm_move_epi64(x), 4),
+    );
+    let x = arch::_mm_xor_si128(
+        arch::_mm_clmulepi64_si128(x, k5k6, 0x11),
+        arch::_mm_move_epi64(x),
+    );
+
+    // Perform a Barrett reduction from our now 64 bits to 32 bits, this time
+    // in the plain (non-reflected) variant described at the end of the paper.
+    let pu = arch::_mm_set_epi64x(keys.mu as i64, keys.p as i64);
+
+    // T1(x) = ⌊R(x) / x^32⌋ • μ
+    let t1 = arch::_mm_clmulepi64_si128(arch::_mm_srli_epi64(x, 32), pu, 0x10);
+    // T2(x) = ⌊T1(x) / x^32⌋ • P(x)
+    let t2 = arch::_mm_clmulepi64_si128(arch::_mm_srli_epi64(t1, 32), pu, 0x00);
+    // C(x) = (R(x) ^ T2(x)) % x^32
+    let c = arch::_mm_cvtsi128_si32(arch::_mm_xor_si128(x, t2)) as u32;
+
+    (c, data)
+}
+
+pub(super) unsafe fn reduce128(
+    a: arch::__m128i,
+    b: arch::__m128i,
+    keys: arch::__m128i,
+) -> arch::__m128i {
     let t1 = arch::_mm_clmulepi64_si128(a, keys, 0x00);
     let t2 = arch::_mm_clmulepi64_si128(a, keys, 0x11);
     arch::_mm_xor_si128(arch::_mm_xor_si128(b, t1), t2)
 }
 
-unsafe fn get(a: &mut &[u8]) -> arch::__m128i {
+pub(super) unsafe fn get(a: &mut &[u8]) -> arch::__m128i {
     debug_assert!(a.len() >= 16);
     let r = arch::_mm_loadu_si128(a.as_ptr() as *const arch::__m128i);
     *a = &a[16..];
     r
 }
 
+unsafe fn get_swapped(a: &mut &[u8], mask: arch::__m128i) -> arch::__m128i {
+    arch::_mm_shuffle_epi8(get(a), mask)
+}
+
 #[cfg(test)]
 mod test {
     quickcheck::quickcheck! {
@@ -203,5 +379,34 @@ mod test {
             }
             pclmulqdq.finalize() == baseline.finalize()
         }
+
+        fn multiply_against_baseline(a: u32, b: u32) -> bool {
+            super::State::new(0).expect("not supported");
+            let expected = crate::combine::multiply(a, b, 0xedb88320);
+            // SAFETY: `State::new` only succeeds if all required instructions
+            //         are supported by the CPU.
+            unsafe { super::multiply(a, b) == expected }
+        }
+    }
+
+    #[test]
+    fn combine_golden() {
+        let state = super::State::new(0).expect("not supported");
+        let combine = |crc1: u32, crc2: u32, len2: u64| {
+            let mut state = super::State::new(crc1).unwrap();
+            state.combine(crc2, &state.combine_op(len2));
+            state.finalize()
+        };
+        assert_eq!(state.combine_op(0xa59d), crate::CombineOp::new(0xa59d));
+        assert_eq!(combine(0x0, 0x1, 0x0), 0x0);
+        assert_eq!(combine(0xc401f8c9, 0x00000000, 0x0), 0xc401f8c9);
+        assert_eq!(combine(0x7cba3d5e, 0xe7466d39, 0xb), 0x76365c4f);
+        assert_eq!(combine(0x576c62d6, 0x123256e1, 0x47), 0x579a636);
+        assert_eq!(combine(0x4f626f9a, 0x9e5ccbf5, 0xa59d), 0x98d43168);
+        assert_eq!(combine(0xa09b8a88, 0x815b0f48, 0x40f39511), 0xd7a5f79);
+        assert_eq!(
+            combine(0x7f6a4306, 0xbc929646, 0x828cde72b3e25301),
+            0xef922dda
+        );
     }
 }
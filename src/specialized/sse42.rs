@@ -0,0 +1,178 @@
+//! Specialized CRC-32C code for the x86-64 CPU architecture, using the
+//! dedicated SSE4.2 `crc32` instruction.
+//!
+//! The `crc32` instruction has a latency of three cycles but a throughput of
+//! one per cycle, so a single dependency chain only reaches a third of the
+//! possible speed. Large inputs are therefore split into three streams of
+//! equal length that are checksummed independently and then merged using
+//! carry-less multiplication, following the approach of:
+//!
+//! Gopal, V., Guilford, J., Ozturk, E., Wolrich, G., Feghali, W., Dixon, M. (2011).
+//! _Fast CRC Computation for iSCSI Polynomial Using CRC32 Instruction_. Intel.
+
+use core::arch::x86_64 as arch;
+
+#[derive(Clone)]
+pub struct State {
+    state: u32,
+}
+
+impl State {
+    #[cfg(not(feature = "std"))]
+    pub fn new(state: u32) -> Option<Self> {
+        if cfg!(target_feature = "pclmulqdq") && cfg!(target_feature = "sse4.2") {
+            // SAFETY: The conditions above ensure that all
+            //         required instructions are supported by the CPU.
+            Some(Self { state })
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn new(state: u32) -> Option<Self> {
+        if is_x86_feature_detected!("pclmulqdq") && is_x86_feature_detected!("sse4.2") {
+            // SAFETY: The conditions above ensure that all
+            //         required instructions are supported by the CPU.
+            Some(Self { state })
+        } else {
+            None
+        }
+    }
+
+    pub fn update(&mut self, buf: &[u8]) {
+        // SAFETY: The `State::new` constructor ensures that all
+        //         required instructions are supported by the CPU.
+        self.state = unsafe { calculate(self.state, buf) }
+    }
+
+    pub fn finalize(self) -> u32 {
+        self.state
+    }
+
+    pub fn reset(&mut self) {
+        self.state = 0;
+    }
+
+    pub fn combine(&mut self, other: u32, amount: u64) {
+        self.state = crate::crc32c::combine::combine(self.state, other, amount);
+    }
+}
+
+// Stream lengths, in 8-byte words.
+const LONG: usize = 1024;
+const SHORT: usize = 32;
+
+// Shift constants for merging the streams. Each is x^(64*n - 33) modulo the
+// (reflected) Castagnoli polynomial, where n is the number of words to shift
+// by. The 33 accounts for the extra factor of x introduced by the carry-less
+// multiplication of two reflected values, plus the x^32 introduced by the
+// final `crc32` instruction which reduces the 64-bit product.
+const K_LONG: i32 = 0x54a86326;
+const K_LONG_2: i32 = 0x1dc403cc;
+const K_SHORT: i32 = 0xb9e02b86_u32 as i32;
+const K_SHORT_2: i32 = 0xdd7e3b0c_u32 as i32;
+
+#[target_feature(enable = "pclmulqdq", enable = "sse4.2")]
+unsafe fn calculate(crc: u32, data: &[u8]) -> u32 {
+    let mut crc0 = u64::from(!crc);
+    let (pre_quad, mut quads, post_quad) = data.align_to::<u64>();
+
+    crc0 = pre_quad
+        .iter()
+        .fold(crc0, |acc, &b| u64::from(arch::_mm_crc32_u8(acc as u32, b)));
+
+    while quads.len() >= 3 * LONG {
+        crc0 = interleave(crc0, &quads[..3 * LONG], K_LONG, K_LONG_2);
+        quads = &quads[3 * LONG..];
+    }
+
+    while quads.len() >= 3 * SHORT {
+        crc0 = interleave(crc0, &quads[..3 * SHORT], K_SHORT, K_SHORT_2);
+        quads = &quads[3 * SHORT..];
+    }
+
+    crc0 = quads
+        .iter()
+        .fold(crc0, |acc, &q| arch::_mm_crc32_u64(acc, q));
+
+    crc0 = post_quad
+        .iter()
+        .fold(crc0, |acc, &b| u64::from(arch::_mm_crc32_u8(acc as u32, b)));
+
+    !(crc0 as u32)
+}
+
+// Checksums three consecutive streams of equal length in parallel, and merges
+// the results into a single CRC register value.
+#[inline(always)]
+unsafe fn interleave(crc: u64, quads: &[u64], k: i32, k2: i32) -> u64 {
+    let n = quads.len() / 3;
+    let (a, rest) = quads.split_at(n);
+    let (b, c) = rest.split_at(n);
+
+    let mut crc0 = crc;
+    let mut crc1 = 0;
+    let mut crc2 = 0;
+    for i in 0..n {
+        crc0 = arch::_mm_crc32_u64(crc0, a[i]);
+        crc1 = arch::_mm_crc32_u64(crc1, b[i]);
+        crc2 = arch::_mm_crc32_u64(crc2, c[i]);
+    }
+
+    shift(crc0, k2) ^ shift(crc1, k) ^ crc2
+}
+
+// Multiplies the CRC register value by the given shift constant, modulo the
+// Castagnoli polynomial.
+#[inline(always)]
+unsafe fn shift(crc: u64, k: i32) -> u64 {
+    let product = arch::_mm_clmulepi64_si128(
+        arch::_mm_cvtsi32_si128(crc as i32),
+        arch::_mm_cvtsi32_si128(k),
+        0x00,
+    );
+    arch::_mm_crc32_u64(0, arch::_mm_cvtsi128_si64(product) as u64)
+}
+
+#[cfg(test)]
+mod test {
+    quickcheck::quickcheck! {
+        fn check_against_baseline(init: u32, chunks: Vec<(Vec<u8>, usize)>) -> bool {
+            let mut baseline = super::super::super::crc32c::baseline::State::new(init);
+            let mut sse42 = super::State::new(init).expect("not supported");
+            for (chunk, mut offset) in chunks {
+                // simulate random alignments by offsetting the slice by up to 15 bytes
+                offset &= 0xF;
+                if chunk.len() <= offset {
+                    baseline.update(&chunk);
+                    sse42.update(&chunk);
+                } else {
+                    baseline.update(&chunk[offset..]);
+                    sse42.update(&chunk[offset..]);
+                }
+            }
+            sse42.finalize() == baseline.finalize()
+        }
+
+        fn check_long_against_baseline(init: u32, seed: u8, len: u16, offset: u8) -> bool {
+            // Long enough to reach the interleaved loops.
+            let len = usize::from(len) + 100_000;
+            super::super::check_long_against_baseline(
+                seed,
+                len,
+                offset,
+                |buf| {
+                    let mut baseline = super::super::super::crc32c::baseline::State::new(init);
+                    baseline.update(buf);
+                    baseline.finalize()
+                },
+                |buf| {
+                    let mut sse42 = super::State::new(init).expect("not supported");
+                    sse42.update(buf);
+                    sse42.finalize()
+                },
+            )
+        }
+    }
+}
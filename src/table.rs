@@ -627,3 +627,48 @@ pub static CRC32_TABLE: [[u32; 256]; 16] = [
         0x24B98D25, 0x8AD11CB4, 0xA319A846, 0x0D7139D7,
     ],
 ];
+
+// Builds the slice-by-N lookup tables for `poly`, where `table[k][i]` is the CRC
+// of the byte `i` followed by `k` zero bytes. Registers narrower than 32 bits
+// are kept right-aligned if `reflected` and left-aligned otherwise, with `poly`
+// given in the same orientation.
+pub const fn make_table<const N: usize>(poly: u32, reflected: bool) -> [[u32; 256]; N] {
+    let mut table = [[0u32; 256]; N];
+
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = if reflected {
+            i as u32
+        } else {
+            (i as u32) << 24
+        };
+        let mut j = 0;
+        while j < 8 {
+            crc = if reflected {
+                (crc >> 1) ^ ((crc & 1).wrapping_neg() & poly)
+            } else {
+                (crc << 1) ^ ((crc >> 31).wrapping_neg() & poly)
+            };
+            j += 1;
+        }
+        table[0][i] = crc;
+        i += 1;
+    }
+
+    let mut k = 1;
+    while k < N {
+        let mut i = 0;
+        while i < 256 {
+            let prev = table[k - 1][i];
+            table[k][i] = if reflected {
+                (prev >> 8) ^ table[0][(prev & 0xff) as usize]
+            } else {
+                (prev << 8) ^ table[0][(prev >> 24) as usize]
+            };
+            i += 1;
+        }
+        k += 1;
+    }
+
+    table
+}